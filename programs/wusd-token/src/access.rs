@@ -1,10 +1,70 @@
 use anchor_lang::prelude::*;
 
-/// 访问级别枚举，用于定义不同的访问权限级别
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
-pub enum AccessLevel {
-    /// 信用操作（接收代币）
-    Credit,
-    /// 借记操作（发送代币）
-    Debit,
-}
\ No newline at end of file
+/// 操作员权限位，记录在`OperatorRecord.permissions`中，管理员不受限制
+pub struct Permission;
+
+impl Permission {
+    /// 发起转账（`transfer`）
+    pub const CAN_TRANSFER: u32 = 1 << 0;
+    /// 使用授权额度转账（`transfer_from`）
+    pub const CAN_TRANSFER_FROM: u32 = 1 << 1;
+    /// 销毁及发起赎回（`burn`、`burn_from`、`request_redemption`）
+    pub const CAN_BURN: u32 = 1 << 2;
+    /// 铸币（`mint`），铸币本身由Minter角色及`MinterState`额度授权，此位不参与校验
+    pub const CAN_MINT_TO: u32 = 1 << 3;
+    /// 冻结/解冻账户（`freeze_account`、`unfreeze_account`），与Freezer角色二者满足其一即可
+    pub const CAN_FREEZE: u32 = 1 << 4;
+    /// 代他人中继提交许可签名（`permit`），owner自行提交时无需此权限
+    pub const CAN_RELAY_PERMIT: u32 = 1 << 5;
+    /// 全部权限
+    pub const ALL: u32 = Self::CAN_TRANSFER
+        | Self::CAN_TRANSFER_FROM
+        | Self::CAN_BURN
+        | Self::CAN_MINT_TO
+        | Self::CAN_FREEZE
+        | Self::CAN_RELAY_PERMIT;
+
+    /// 是否只包含已定义的权限位
+    pub fn is_valid(permissions: u32) -> bool {
+        permissions & !Self::ALL == 0
+    }
+}
+
+/// 角色枚举，每个角色由独立的角色账户记录成员
+///
+/// 判别值即角色账户PDA种子，已部署的取值不可修改，新增角色只能追加在末尾
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum Role {
+    /// 铸币，由`configure_minter`/`remove_minter`同步授予和撤销
+    Minter = 0,
+    /// 销毁
+    Burner = 1,
+    /// 暂停/恢复合约
+    Pauser = 2,
+    /// 冻结/解冻账户
+    Freezer = 3,
+    /// 管理操作员
+    OperatorAdmin = 4,
+    /// 管理手续费
+    FeeManager = 5,
+    /// 救援误转入程序账户的其他代币
+    Rescuer = 6,
+    /// 配置铸币者及其铸币额度
+    MasterMinter = 7,
+    /// 清除被冻结账户的余额
+    Wiper = 8,
+    /// 管理黑名单
+    Blacklister = 9,
+    /// 管理白名单
+    Allowlister = 10,
+    /// 管理KYC记录及等级限额
+    Compliance = 11,
+}
+
+impl Role {
+    /// 角色账户PDA种子
+    pub fn seed(&self) -> [u8; 1] {
+        [*self as u8]
+    }
+}
@@ -44,4 +44,78 @@ pub enum WusdError {
     InvalidMint, 
     #[msg("Expired permit")]
     ExpiredPermit,
+    #[msg("Invalid signature")]
+    InvalidSignature,
+    #[msg("Missing ed25519 signature verification instruction")]
+    MissingSignatureVerification,
+    #[msg("Permit scope does not allow this operation")]
+    InvalidPermitScope,
+    #[msg("Program delegate not approved on token account")]
+    DelegateNotApproved,
+    #[msg("Mint decimals do not match mint state")]
+    InvalidDecimals,
+    #[msg("Account does not have the required role")]
+    MissingRole,
+    #[msg("Too many role members")]
+    TooManyRoleMembers,
+    #[msg("Role member not found")]
+    RoleMemberNotFound,
+    #[msg("No pending admin transfer")]
+    NoPendingAdmin,
+    #[msg("Not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Minter allowance exceeded")]
+    MinterAllowanceExceeded,
+    #[msg("Mint would exceed max supply")]
+    MaxSupplyExceeded,
+    #[msg("Mint rate limit exceeded")]
+    MintRateLimitExceeded,
+    #[msg("Invalid rate limit configuration")]
+    InvalidRateLimit,
+    #[msg("Reference id too long")]
+    ReferenceTooLong,
+    #[msg("Redemption request is not pending")]
+    RedemptionNotPending,
+    #[msg("Permanent delegate is not configured on mint")]
+    PermanentDelegateNotConfigured,
+    #[msg("Account is blacklisted")]
+    Blacklisted,
+    #[msg("Token metadata field too long")]
+    MetadataTooLong,
+    #[msg("Invalid transfer fee configuration")]
+    InvalidTransferFee,
+    #[msg("Memo too long")]
+    MemoTooLong,
+    #[msg("Memo program account is required when a memo is provided")]
+    MemoProgramMissing,
+    #[msg("Operator label too long")]
+    OperatorLabelTooLong,
+    #[msg("Invalid expiration")]
+    InvalidExpiration,
+    #[msg("Invalid operator record")]
+    InvalidOperatorRecord,
+    #[msg("Invalid operator permissions")]
+    InvalidPermissions,
+    #[msg("Recipient is not on the allowlist")]
+    RecipientNotAllowlisted,
+    #[msg("Invalid allowlist entry account")]
+    InvalidAllowlistEntry,
+    #[msg("KYC record has expired")]
+    KycExpired,
+    #[msg("Invalid KYC tier limits account")]
+    InvalidKycTierLimits,
+    #[msg("Invalid jurisdiction code")]
+    InvalidJurisdiction,
+    #[msg("Amount exceeds the single transfer limit of the KYC tier")]
+    TransferLimitExceeded,
+    #[msg("Amount exceeds the daily outflow limit of the KYC tier")]
+    DailyOutflowLimitExceeded,
+    #[msg("Recipient balance would exceed the balance limit of the KYC tier")]
+    BalanceLimitExceeded,
+    #[msg("Account is already migrated")]
+    AlreadyMigrated,
+    #[msg("Invalid KYC tier")]
+    InvalidKycTier,
+    #[msg("Daily outflow account must be initialized before transferring")]
+    KycOutflowNotInitialized,
 }
\ No newline at end of file
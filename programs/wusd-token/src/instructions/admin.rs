@@ -0,0 +1,218 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{AccessRegistryState, AuthorityState, RoleState};
+
+/// 提议新的管理员，需由新管理员调用`accept_admin`后生效
+/// * `ctx` - 上下文
+/// * `new_admin` - 新管理员地址
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let authority_state = &mut ctx.accounts.authority_state;
+    require!(
+        authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    authority_state.pending_admin = Some(new_admin);
+
+    emit!(AdminTransferProposed {
+        mint: ctx.accounts.token_mint.key(),
+        admin: authority_state.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+/// 新管理员接受管理权，已初始化的访问注册表的管理员同时转移
+/// * `ctx` - 上下文
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let authority_state = &mut ctx.accounts.authority_state;
+    require!(authority_state.pending_admin.is_some(), WusdError::NoPendingAdmin);
+    require!(
+        authority_state.is_pending_admin(ctx.accounts.new_admin.key()),
+        WusdError::NotPendingAdmin
+    );
+
+    let previous_admin = authority_state.admin;
+    authority_state.admin = ctx.accounts.new_admin.key();
+    authority_state.pending_admin = None;
+    if let Some(access_registry) = ctx.accounts.access_registry.as_mut() {
+        access_registry.authority = authority_state.admin;
+    }
+
+    emit!(AdminTransferred {
+        mint: ctx.accounts.token_mint.key(),
+        previous_admin,
+        new_admin: authority_state.admin,
+    });
+
+    Ok(())
+}
+
+/// 取消尚未被接受的管理员转移
+/// * `ctx` - 上下文
+pub fn cancel_admin_transfer(ctx: Context<ProposeAdmin>) -> Result<()> {
+    let authority_state = &mut ctx.accounts.authority_state;
+    require!(
+        authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+    let pending_admin = authority_state.pending_admin.take()
+        .ok_or(error!(WusdError::NoPendingAdmin))?;
+
+    emit!(AdminTransferCancelled {
+        mint: ctx.accounts.token_mint.key(),
+        admin: authority_state.admin,
+        pending_admin,
+    });
+
+    Ok(())
+}
+
+/// 将旧版权限状态账户（admin/minter/pauser）迁移为当前布局
+///
+/// 账户缩小到`AuthorityState::SIZE`，多余租金退还给调用者。
+/// 旧版pauser被授予Pauser角色；旧版minter不再有效，需通过`configure_minter`重新配置。
+/// * `ctx` - 上下文
+pub fn migrate_authority_state(ctx: Context<MigrateAuthorityState>) -> Result<()> {
+    let authority_info = ctx.accounts.authority_state.to_account_info();
+    let (admin, pauser) = {
+        let data = authority_info.try_borrow_data()?;
+        require!(
+            data.len() == AuthorityState::LEGACY_DATA_LEN
+                && data[..8] == AuthorityState::DISCRIMINATOR,
+            WusdError::AlreadyMigrated
+        );
+        (
+            Pubkey::try_from(&data[8..40]).unwrap(),
+            Pubkey::try_from(&data[72..104]).unwrap(),
+        )
+    };
+    require!(admin == ctx.accounts.authority.key(), WusdError::Unauthorized);
+
+    // 缩小账户并写入新布局
+    authority_info.realloc(AuthorityState::SIZE, false)?;
+    AuthorityState::initialize(admin)
+        .try_serialize(&mut &mut authority_info.try_borrow_mut_data()?[..])?;
+    let rent_exempt = Rent::get()?.minimum_balance(AuthorityState::SIZE);
+    let excess = authority_info.lamports().saturating_sub(rent_exempt);
+    **authority_info.try_borrow_mut_lamports()? -= excess;
+    **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += excess;
+
+    // 保留旧版pauser的暂停权限
+    let pauser_role = &mut ctx.accounts.pauser_role;
+    if pauser_role.is_uninitialized() {
+        pauser_role.mint = ctx.accounts.token_mint.key();
+        pauser_role.role = Role::Pauser;
+        pauser_role.bump = *ctx.bumps.get("pauser_role").unwrap();
+    }
+    if pauser != admin {
+        pauser_role.grant(pauser)?;
+    }
+
+    emit!(AuthorityStateMigrated {
+        mint: ctx.accounts.token_mint.key(),
+        admin,
+        pauser,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    /// 访问注册表，其管理员随之转移；尚未初始化注册表的mint传入None
+    #[account(
+        mut,
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Option<Account<'info, AccessRegistryState>>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuthorityState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    /// CHECK: 旧版权限状态账户，布局与当前`AuthorityState`不同，在指令中手动解析
+    #[account(
+        mut,
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub authority_state: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RoleState::SIZE,
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Pauser.seed()],
+        bump
+    )]
+    pub pauser_role: Account<'info, RoleState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 管理员转移提议事件
+#[event]
+pub struct AdminTransferProposed {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// 管理员转移完成事件
+#[event]
+pub struct AdminTransferred {
+    pub mint: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+/// 管理员转移取消事件
+#[event]
+pub struct AdminTransferCancelled {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+/// 权限状态迁移事件
+#[event]
+pub struct AuthorityStateMigrated {
+    pub mint: Pubkey,
+    pub admin: Pubkey,
+    pub pauser: Pubkey,
+}
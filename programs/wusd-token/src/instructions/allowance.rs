@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{self, Token2022};
+use crate::error::WusdError;
+use crate::state::AllowanceState;
+
+/// 设置授权额度，覆盖原有额度
+/// * `ctx` - 授权上下文
+/// * `amount` - 授权额度
+pub fn approve(ctx: Context<ManageAllowance>, amount: u64) -> Result<()> {
+    ctx.accounts.load_allowance(*ctx.bumps.get("allowance").unwrap());
+    ctx.accounts.allowance.approve(amount);
+    ctx.accounts.emit_approval();
+    Ok(())
+}
+
+/// 增加授权额度
+/// * `ctx` - 授权上下文
+/// * `added_value` - 增加的额度
+pub fn increase_allowance(ctx: Context<ManageAllowance>, added_value: u64) -> Result<()> {
+    ctx.accounts.load_allowance(*ctx.bumps.get("allowance").unwrap());
+    ctx.accounts.allowance.increase_allowance(added_value)?;
+    ctx.accounts.emit_approval();
+    Ok(())
+}
+
+/// 减少授权额度
+/// * `ctx` - 授权上下文
+/// * `subtracted_value` - 减少的额度
+pub fn decrease_allowance(ctx: Context<ManageAllowance>, subtracted_value: u64) -> Result<()> {
+    ctx.accounts.load_allowance(*ctx.bumps.get("allowance").unwrap());
+    ctx.accounts.allowance.decrease_allowance(subtracted_value)?;
+    ctx.accounts.emit_approval();
+    Ok(())
+}
+
+/// 撤销授权，额度清零
+///
+/// 授权账户不会被关闭，以保留签名许可的随机数
+/// * `ctx` - 授权上下文
+pub fn revoke(ctx: Context<ManageAllowance>) -> Result<()> {
+    ctx.accounts.load_allowance(*ctx.bumps.get("allowance").unwrap());
+    ctx.accounts.allowance.approve(0);
+    ctx.accounts.emit_approval();
+    Ok(())
+}
+
+/// 将owner的代币账户委托给程序控制的PDA
+///
+/// 每个代币账户只需执行一次，此后`transfer_from`/`burn_from`由该PDA签名，
+/// 实际可用额度以程序内的授权账户为准
+/// * `ctx` - 委托上下文
+pub fn approve_delegate(ctx: Context<ApproveDelegate>) -> Result<()> {
+    token_2022::approve(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::Approve {
+                to: ctx.accounts.token_account.to_account_info(),
+                delegate: ctx.accounts.delegate.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ),
+        u64::MAX,
+    )?;
+
+    emit!(DelegateApproved {
+        owner: ctx.accounts.owner.key(),
+        token_account: ctx.accounts.token_account.key(),
+        delegate: ctx.accounts.delegate.key(),
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ WusdError::InvalidOwner,
+        constraint = token_account.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub token_account: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    /// CHECK: 程序控制的委托PDA，仅作为SPL委托地址
+    #[account(
+        seeds = [b"delegate", token_mint.key().as_ref()],
+        bump
+    )]
+    pub delegate: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct ManageAllowance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: 被授权者，仅用于派生授权PDA
+    pub spender: AccountInfo<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = AllowanceState::SIZE,
+        seeds = [b"allowance", token_mint.key().as_ref(), owner.key().as_ref(), spender.key().as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, AllowanceState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ManageAllowance<'info> {
+    /// 首次使用时初始化授权账户
+    fn load_allowance(&mut self, bump: u8) {
+        if self.allowance.is_uninitialized() {
+            self.allowance.set_inner(AllowanceState::initialize(
+                self.token_mint.key(),
+                self.owner.key(),
+                self.spender.key(),
+                bump,
+            ));
+        }
+    }
+
+    fn emit_approval(&self) {
+        emit!(ApprovalEvent {
+            mint: self.token_mint.key(),
+            owner: self.owner.key(),
+            spender: self.spender.key(),
+            amount: self.allowance.amount,
+        });
+    }
+}
+
+/// 授权事件，每次授权额度变化时发出，记录变化后的额度
+#[event]
+pub struct ApprovalEvent {
+    /// 代币铸币账户
+    pub mint: Pubkey,
+    /// 代币所有者地址
+    pub owner: Pubkey,
+    /// 被授权者地址
+    pub spender: Pubkey,
+    /// 当前授权额度
+    pub amount: u64,
+}
+
+/// 委托事件，记录代币账户已委托给程序PDA
+#[event]
+pub struct DelegateApproved {
+    /// 代币所有者地址
+    pub owner: Pubkey,
+    /// 被委托的代币账户
+    pub token_account: Pubkey,
+    /// 程序控制的委托PDA
+    pub delegate: Pubkey,
+}
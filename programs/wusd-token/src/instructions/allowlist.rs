@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{AccessRegistryState, AllowlistEntry, AuthorityState, RoleState};
+use crate::utils::{close_pda_account, create_pda_account};
+
+/// 开启或关闭白名单（闭环）模式，仅管理员可调用
+/// * `ctx` - 上下文
+/// * `enabled` - 是否启用白名单模式
+pub fn set_allowlist_mode(ctx: Context<SetAllowlistMode>, enabled: bool) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    ctx.accounts.access_registry.allowlist_enabled = enabled;
+
+    emit!(AllowlistModeUpdated {
+        mint: ctx.accounts.token_mint.key(),
+        enabled,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 批量将钱包地址加入白名单，已在白名单中的地址会被跳过
+/// * `ctx` - 上下文，`remaining_accounts`按`accounts`顺序传入各白名单条目PDA（可写）
+/// * `accounts` - 钱包地址列表
+pub fn add_to_allowlist<'info>(
+    ctx: Context<'_, '_, '_, 'info, ManageAllowlist<'info>>,
+    accounts: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.allowlister_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(
+        ctx.remaining_accounts.len() == accounts.len(),
+        WusdError::InvalidAllowlistEntry
+    );
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    for (account, entry_info) in accounts.iter().zip(ctx.remaining_accounts.iter()) {
+        let bump = verify_entry_address(ctx.program_id, &mint_key, account, entry_info)?;
+        if !entry_info.data_is_empty() {
+            continue;
+        }
+
+        create_pda_account(
+            &ctx.accounts.authority.to_account_info(),
+            entry_info,
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.program_id,
+            AllowlistEntry::SIZE,
+            &[b"allowlist", mint_key.as_ref(), account.as_ref(), &[bump]],
+        )?;
+        let entry = AllowlistEntry {
+            mint: mint_key,
+            account: *account,
+            added_by: ctx.accounts.authority.key(),
+            added_at: now,
+            bump,
+        };
+        entry.try_serialize(&mut &mut entry_info.try_borrow_mut_data()?[..])?;
+    }
+
+    emit!(AllowlistUpdated {
+        mint: mint_key,
+        accounts,
+        allowed: true,
+        authority: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// 批量将钱包地址移出白名单，关闭其白名单条目，不在白名单中的地址会被跳过
+/// * `ctx` - 上下文，`remaining_accounts`按`accounts`顺序传入各白名单条目PDA（可写）
+/// * `accounts` - 钱包地址列表
+pub fn remove_from_allowlist<'info>(
+    ctx: Context<'_, '_, '_, 'info, ManageAllowlist<'info>>,
+    accounts: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.allowlister_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(
+        ctx.remaining_accounts.len() == accounts.len(),
+        WusdError::InvalidAllowlistEntry
+    );
+
+    let mint_key = ctx.accounts.token_mint.key();
+    for (account, entry_info) in accounts.iter().zip(ctx.remaining_accounts.iter()) {
+        verify_entry_address(ctx.program_id, &mint_key, account, entry_info)?;
+        if entry_info.data_is_empty() {
+            continue;
+        }
+        require_keys_eq!(*entry_info.owner, crate::ID, WusdError::InvalidAllowlistEntry);
+        close_pda_account(entry_info, &ctx.accounts.authority.to_account_info())?;
+    }
+
+    emit!(AllowlistUpdated {
+        mint: mint_key,
+        accounts,
+        allowed: false,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 校验白名单条目PDA地址，返回bump
+fn verify_entry_address(
+    program_id: &Pubkey,
+    mint: &Pubkey,
+    account: &Pubkey,
+    entry_info: &AccountInfo,
+) -> Result<u8> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"allowlist", mint.as_ref(), account.as_ref()],
+        program_id,
+    );
+    require_keys_eq!(entry_info.key(), expected, WusdError::InvalidAllowlistEntry);
+    Ok(bump)
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistMode<'info> {
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    #[account(
+        mut,
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+}
+
+#[derive(Accounts)]
+pub struct ManageAllowlist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Allowlister.seed()],
+        bump = allowlister_role.bump
+    )]
+    pub allowlister_role: Account<'info, RoleState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// 白名单模式变更事件
+#[event]
+pub struct AllowlistModeUpdated {
+    pub mint: Pubkey,
+    pub enabled: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 白名单批量变更事件
+#[event]
+pub struct AllowlistUpdated {
+    pub mint: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub allowed: bool,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{BlacklistEntry, RoleState};
+
+/// 将钱包地址列入黑名单，该地址名下的所有代币账户均无法收发代币
+/// * `ctx` - 上下文
+/// * `account` - 被列入黑名单的钱包地址
+pub fn blacklist(ctx: Context<Blacklist>, account: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.blacklister_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.blacklist_entry.set_inner(BlacklistEntry {
+        mint: ctx.accounts.token_mint.key(),
+        account,
+        blacklisted_by: ctx.accounts.authority.key(),
+        blacklisted_at: now,
+        bump: *ctx.bumps.get("blacklist_entry").unwrap(),
+    });
+
+    emit!(Blacklisted {
+        mint: ctx.accounts.token_mint.key(),
+        account,
+        authority: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// 将钱包地址移出黑名单，关闭其黑名单条目
+/// * `ctx` - 上下文
+/// * `account` - 被移出黑名单的钱包地址
+pub fn unblacklist(ctx: Context<Unblacklist>, account: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.blacklister_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    emit!(Unblacklisted {
+        mint: ctx.accounts.token_mint.key(),
+        account,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(account: Pubkey)]
+pub struct Blacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Blacklister.seed()],
+        bump = blacklister_role.bump
+    )]
+    pub blacklister_role: Account<'info, RoleState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = BlacklistEntry::SIZE,
+        seeds = [b"blacklist", token_mint.key().as_ref(), account.as_ref()],
+        bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(account: Pubkey)]
+pub struct Unblacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Blacklister.seed()],
+        bump = blacklister_role.bump
+    )]
+    pub blacklister_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"blacklist", token_mint.key().as_ref(), account.as_ref()],
+        bump = blacklist_entry.bump
+    )]
+    pub blacklist_entry: Account<'info, BlacklistEntry>,
+}
+
+/// 列入黑名单事件
+#[event]
+pub struct Blacklisted {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 移出黑名单事件
+#[event]
+pub struct Unblacklisted {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
 use anchor_spl::token_2022::Token2022;
 use anchor_spl::token_2022::{self, burn as token_burn};
-use crate::access::AccessLevel;
+use crate::access::Permission;
 use crate::error::WusdError;
-use crate::state::{AuthorityState, MintState, AccessRegistryState, PauseState};
+use crate::state::{AuthorityState, MintState, AccessRegistryState, OperatorRecord, PauseState, AllowanceState};
+use crate::utils::require_has_access;
+use crate::instructions::allowance::ApprovalEvent;
 
 /// 销毁WUSD代币
 /// * `ctx` - 销毁上下文
@@ -28,7 +31,9 @@ pub fn burn(ctx: Context<Burn>, amount: u64) -> Result<()> {
     require!(
         ctx.accounts.access_registry.has_access(
             ctx.accounts.authority.key(),
-            AccessLevel::Debit
+            Permission::CAN_BURN,
+            ctx.accounts.operator_record.as_deref(),
+            Clock::get()?.unix_timestamp,
         ),
         WusdError::AccessDenied
     );
@@ -60,6 +65,72 @@ pub fn burn(ctx: Context<Burn>, amount: u64) -> Result<()> {
     Ok(())
 } 
 
+/// 使用许可额度代owner销毁WUSD代币
+/// * `ctx` - 销毁上下文
+/// * `amount` - 销毁数量
+pub fn burn_from(ctx: Context<BurnFrom>, amount: u64) -> Result<()> {
+    // 验证授权有效性
+    let current_time = Clock::get()?.unix_timestamp;
+    require!(
+        ctx.accounts.allowance.expiration > current_time,
+        WusdError::ExpiredPermit
+    );
+    require!(
+        ctx.accounts.allowance.amount >= amount,
+        WusdError::InsufficientAllowance
+    );
+    ctx.accounts.allowance.validate_burn_scope()?;
+
+    // 检查访问权限
+    require_has_access(
+        ctx.accounts.spender.key(),
+        Permission::CAN_BURN,
+        Some(amount),
+        &ctx.accounts.pause_state,
+        Some(&ctx.accounts.access_registry),
+        ctx.accounts.operator_record.as_deref(),
+    )?;
+
+    // 验证余额充足
+    require!(
+        ctx.accounts.token_account.amount >= amount,
+        WusdError::InsufficientBalance
+    );
+
+    // 使用程序控制的委托PDA签名销毁
+    let mint_key = ctx.accounts.mint.key();
+    let delegate_bump = *ctx.bumps.get("delegate").unwrap();
+    let seeds: &[&[u8]] = &[b"delegate", mint_key.as_ref(), &[delegate_bump]];
+    token_burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.token_account.to_account_info(),
+                authority: ctx.accounts.delegate.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount
+    )?;
+
+    // 更新授权额度，单次许可使用后失效
+    ctx.accounts.allowance.consume(amount)?;
+    emit!(ApprovalEvent {
+        mint: ctx.accounts.mint.key(),
+        owner: ctx.accounts.owner.key(),
+        spender: ctx.accounts.spender.key(),
+        amount: ctx.accounts.allowance.amount,
+    });
+
+    emit!(BurnEvent {
+        burner: ctx.accounts.spender.key(),
+        amount
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct Burn<'info> {
     #[account(mut)]
@@ -73,11 +144,105 @@ pub struct Burn<'info> {
     #[account(mut)]
     pub token_account: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
     pub token_program: Program<'info, Token2022>, 
+    #[account(
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump,
+        constraint = mint_state.mint == mint.key() @ WusdError::InvalidMint,
+        constraint = mint_state.decimals == mint.decimals @ WusdError::InvalidDecimals
+    )]
     pub mint_state: Account<'info, MintState>,
+    #[account(
+        seeds = [b"pause_state", mint.key().as_ref()],
+        bump,
+    )]
     pub pause_state: Account<'info, PauseState>,
-    pub access_registry: Account<'info, AccessRegistryState>, 
+    #[account(
+        seeds = [b"access_registry", mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+    /// CHECK: 代币所有者的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), token_account.owner.as_ref()],
+        bump,
+        constraint = owner_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub owner_blacklist: AccountInfo<'info>,
+    /// 调用者的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", mint.key().as_ref(), authority.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
 } 
 
+#[derive(Accounts)]
+pub struct BurnFrom<'info> {
+    pub spender: Signer<'info>,
+    /// CHECK: 代币所有者，仅用于派生授权PDA
+    pub owner: AccountInfo<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ WusdError::InvalidOwner,
+        constraint = token_account.delegate == COption::Some(delegate.key()) @ WusdError::DelegateNotApproved
+    )]
+    pub token_account: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
+    /// CHECK: 程序控制的委托PDA，owner需预先通过`approve_delegate`授权
+    #[account(
+        seeds = [b"delegate", mint.key().as_ref()],
+        bump
+    )]
+    pub delegate: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"allowance", mint.key().as_ref(), owner.key().as_ref(), spender.key().as_ref()],
+        bump = allowance.bump,
+        has_one = owner,
+        has_one = spender,
+    )]
+    pub allowance: Account<'info, AllowanceState>,
+    pub token_program: Program<'info, Token2022>,
+    #[account(
+        seeds = [b"mint_state", mint.key().as_ref()],
+        bump,
+        constraint = mint_state.mint == mint.key() @ WusdError::InvalidMint,
+        constraint = mint_state.decimals == mint.decimals @ WusdError::InvalidDecimals
+    )]
+    pub mint_state: Account<'info, MintState>,
+    #[account(
+        seeds = [b"pause_state", mint.key().as_ref()],
+        bump,
+    )]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(
+        seeds = [b"access_registry", mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+    /// CHECK: owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = owner_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub owner_blacklist: AccountInfo<'info>,
+    /// CHECK: spender的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), spender.key().as_ref()],
+        bump,
+        constraint = spender_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub spender_blacklist: AccountInfo<'info>,
+    /// spender的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", mint.key().as_ref(), spender.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
+}
+
 /// 销毁事件，记录代币销毁的详细信息
 #[event]
 pub struct BurnEvent {
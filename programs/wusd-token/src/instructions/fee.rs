@@ -0,0 +1,189 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::{
+    instruction as transfer_fee_instruction, MAX_FEE_BASIS_POINTS,
+};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{AuthorityState, RoleState};
+
+/// 更新转账手续费率，新费率在两个epoch后生效
+/// * `ctx` - 上下文
+/// * `basis_points` - 手续费率（基点，最大10000）
+/// * `maximum_fee` - 单笔转账手续费上限
+pub fn set_transfer_fee(ctx: Context<SetTransferFee>, basis_points: u16, maximum_fee: u64) -> Result<()> {
+    require!(
+        ctx.accounts.fee_manager_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(basis_points <= MAX_FEE_BASIS_POINTS, WusdError::InvalidTransferFee);
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    invoke_signed(
+        &transfer_fee_instruction::set_transfer_fee(
+            ctx.accounts.token_program.key,
+            &mint_key,
+            &ctx.accounts.authority_state.key(),
+            &[],
+            basis_points,
+            maximum_fee,
+        )?,
+        &[
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.authority_state.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    emit!(TransferFeeUpdated {
+        mint: mint_key,
+        basis_points,
+        maximum_fee,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 将代币账户中扣留的手续费归集到mint，再全部提取到国库账户
+/// * `ctx` - 上下文，`remaining_accounts`为需要归集手续费的代币账户（可写）
+pub fn withdraw_withheld_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawWithheldFees<'info>>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.fee_manager_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    let mint_info = ctx.accounts.token_mint.to_account_info();
+
+    // 归集：该指令无需权限签名
+    if !ctx.remaining_accounts.is_empty() {
+        let sources: Vec<&Pubkey> = ctx.remaining_accounts.iter().map(|a| a.key).collect();
+        let mut account_infos = vec![mint_info.clone()];
+        account_infos.extend_from_slice(ctx.remaining_accounts);
+        invoke(
+            &transfer_fee_instruction::harvest_withheld_tokens_to_mint(
+                ctx.accounts.token_program.key,
+                mint_info.key,
+                &sources,
+            )?,
+            &account_infos,
+        )?;
+    }
+
+    // 提取：由authority_state PDA作为提取权限签名
+    let balance_before = ctx.accounts.treasury.amount;
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    invoke_signed(
+        &transfer_fee_instruction::withdraw_withheld_tokens_from_mint(
+            ctx.accounts.token_program.key,
+            &mint_key,
+            &ctx.accounts.treasury.key(),
+            &ctx.accounts.authority_state.key(),
+            &[],
+        )?,
+        &[
+            mint_info,
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.authority_state.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    ctx.accounts.treasury.reload()?;
+    let amount = ctx
+        .accounts
+        .treasury
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(WusdError::InvalidAmount)?;
+
+    emit!(FeesWithdrawn {
+        mint: mint_key,
+        treasury: ctx.accounts.treasury.key(),
+        amount,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetTransferFee<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::FeeManager.seed()],
+        bump = fee_manager_role.bump
+    )]
+    pub fee_manager_role: Account<'info, RoleState>,
+
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawWithheldFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::FeeManager.seed()],
+        bump = fee_manager_role.bump
+    )]
+    pub fee_manager_role: Account<'info, RoleState>,
+
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    /// 接收手续费的国库账户
+    #[account(
+        mut,
+        constraint = treasury.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// 转账手续费更新事件
+#[event]
+pub struct TransferFeeUpdated {
+    pub mint: Pubkey,
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 手续费提取事件
+#[event]
+pub struct FeesWithdrawn {
+    pub mint: Pubkey,
+    pub treasury: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
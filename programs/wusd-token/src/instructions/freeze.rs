@@ -1,19 +1,46 @@
 use anchor_lang::prelude::*; 
 use crate::error::WusdError;   
-use crate::state::{FreezeState, AuthorityState};
-use anchor_spl::token_interface::{TokenAccount, Token2022};
+use crate::access::{Permission, Role};
+use crate::state::{AccessRegistryState, AuthorityState, FreezeState, OperatorRecord, RoleState};
+use crate::utils::{require_permanent_delegate, transfer_checked_with_hook};
+use anchor_spl::token_2022::{self, burn as token_burn};
+use anchor_spl::token_interface::{Mint, TokenAccount, Token2022};
 
 pub fn initialize_freeze_state(ctx: Context<InitializeFreezeState>) -> Result<()> {
     ctx.accounts.freeze_state.is_frozen = false;
     Ok(())
 }
-/// 冻结账户
-pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
-    // 验证管理员权限
+/// 验证调用者持有Freezer角色，或是拥有`CAN_FREEZE`权限的操作员
+fn require_freezer(
+    authority: Pubkey,
+    freezer_role: Option<&RoleState>,
+    access_registry: &AccessRegistryState,
+    operator_record: Option<&OperatorRecord>,
+) -> Result<()> {
+    if freezer_role.is_some_and(|role| role.has_role(authority)) {
+        return Ok(());
+    }
     require!(
-        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
-        WusdError::Unauthorized
+        access_registry.has_access(
+            authority,
+            Permission::CAN_FREEZE,
+            operator_record,
+            Clock::get()?.unix_timestamp,
+        ),
+        WusdError::AccessDenied
     );
+    Ok(())
+}
+
+/// 冻结账户
+pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
+    // 验证冻结权限
+    require_freezer(
+        ctx.accounts.authority.key(),
+        ctx.accounts.freezer_role.as_deref(),
+        &ctx.accounts.access_registry,
+        ctx.accounts.operator_record.as_deref(),
+    )?;
 
     // 验证账户未被冻结
     require!(
@@ -21,7 +48,19 @@ pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
         WusdError::AccountAlreadyFrozen
     );
 
-    // 冻结账户
+    // 在Token-2022层面冻结代币账户，并同步状态标记
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    token_2022::freeze_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_2022::FreezeAccount {
+            account: ctx.accounts.account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            authority: ctx.accounts.authority_state.to_account_info(),
+        },
+        &[seeds],
+    ))?;
     ctx.accounts.freeze_state.freeze()?;
 
     // 发出冻结事件
@@ -36,11 +75,13 @@ pub fn freeze_account(ctx: Context<FreezeAccount>) -> Result<()> {
 
 /// 解冻账户
 pub fn unfreeze_account(ctx: Context<UnfreezeAccount>) -> Result<()> {
-    // 验证管理员权限
-    require!(
-        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
-        WusdError::Unauthorized
-    );
+    // 验证冻结权限
+    require_freezer(
+        ctx.accounts.authority.key(),
+        ctx.accounts.freezer_role.as_deref(),
+        &ctx.accounts.access_registry,
+        ctx.accounts.operator_record.as_deref(),
+    )?;
 
     // 验证账户已被冻结
     require!(
@@ -48,7 +89,19 @@ pub fn unfreeze_account(ctx: Context<UnfreezeAccount>) -> Result<()> {
         WusdError::AccountNotFrozen
     );
 
-    // 解冻账户
+    // 在Token-2022层面解冻代币账户，并同步状态标记
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    token_2022::thaw_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        token_2022::ThawAccount {
+            account: ctx.accounts.account.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            authority: ctx.accounts.authority_state.to_account_info(),
+        },
+        &[seeds],
+    ))?;
     ctx.accounts.freeze_state.unfreeze();
 
     // 发出解冻事件
@@ -61,6 +114,95 @@ pub fn unfreeze_account(ctx: Context<UnfreezeAccount>) -> Result<()> {
     Ok(())
 }
 
+/// 清除被冻结账户的全部余额
+/// 以mint的永久委托（authority_state PDA）身份操作，
+/// 提供国库账户时将余额转入国库，否则直接销毁。
+/// Token-2022不允许从已冻结账户转出，因此操作前临时解冻，完成后重新冻结
+pub fn wipe_frozen_account<'info>(ctx: Context<'_, '_, '_, 'info, WipeFrozenAccount<'info>>) -> Result<()> {
+    // 验证清除权限
+    require!(
+        ctx.accounts.wiper_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require_permanent_delegate(
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.authority_state.key(),
+    )?;
+
+    let amount = ctx.accounts.token_account.amount;
+    require!(amount > 0, WusdError::InvalidAmount);
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+
+    let frozen = ctx.accounts.token_account.is_frozen();
+    if frozen {
+        token_2022::thaw_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::ThawAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.authority_state.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+    }
+
+    match &ctx.accounts.treasury {
+        Some(treasury) => transfer_checked_with_hook(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::TransferChecked {
+                    from: ctx.accounts.token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: treasury.to_account_info(),
+                    authority: ctx.accounts.authority_state.to_account_info(),
+                },
+                &[seeds],
+            ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            amount,
+            ctx.accounts.token_mint.decimals,
+        )?,
+        None => token_burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::Burn {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    from: ctx.accounts.token_account.to_account_info(),
+                    authority: ctx.accounts.authority_state.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?,
+    }
+
+    if frozen {
+        token_2022::freeze_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::FreezeAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                authority: ctx.accounts.authority_state.to_account_info(),
+            },
+            &[seeds],
+        ))?;
+    }
+
+    emit!(WipeEvent {
+        authority: ctx.accounts.authority.key(),
+        mint: mint_key,
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.token_account.owner,
+        amount,
+        treasury: ctx.accounts.treasury.as_ref().map(|t| t.key()),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct InitializeFreezeState<'info> {
     pub authority: Signer<'info>,
@@ -95,11 +237,41 @@ pub struct FreezeAccount<'info> {
     )]
     pub freeze_state: Account<'info, FreezeState>,
 
-    /// 要冻结的账户
-    /// CHECK: 这个账户仅用于生成PDA种子
-    pub account: AccountInfo<'info>,
+    /// 要冻结的代币账户
+    #[account(
+        mut,
+        constraint = account.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// mint的冻结权限
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    /// Freezer角色账户，由`CAN_FREEZE`操作员调用时可传入None
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Freezer.seed()],
+        bump = freezer_role.bump
+    )]
+    pub freezer_role: Option<Account<'info, RoleState>>,
+
+    #[account(
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Box<Account<'info, AccessRegistryState>>,
 
-    pub authority_state: Account<'info, AuthorityState>,
+    /// 调用者的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", token_mint.key().as_ref(), authority.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
 
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
@@ -119,13 +291,89 @@ pub struct UnfreezeAccount<'info> {
     /// CHECK: 这个账户的安全性由FreezeState结构和程序逻辑保证
     pub freeze_state: Account<'info, FreezeState>,
 
-    /// 要解冻的账户
-    /// CHECK: 这个账户仅用于生成PDA种子
-    pub account: AccountInfo<'info>,
+    /// 要解冻的代币账户
+    #[account(
+        mut,
+        constraint = account.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// mint的冻结权限
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    /// Freezer角色账户，由`CAN_FREEZE`操作员调用时可传入None
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Freezer.seed()],
+        bump = freezer_role.bump
+    )]
+    pub freezer_role: Option<Account<'info, RoleState>>,
+
+    #[account(
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Box<Account<'info, AccessRegistryState>>,
+
+    /// 调用者的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", token_mint.key().as_ref(), authority.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
 
-    pub authority_state: Account<'info, AuthorityState>,
+    pub token_program: Program<'info, Token2022>,
 } 
 
+#[derive(Accounts)]
+pub struct WipeFrozenAccount<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Wiper.seed()],
+        bump = wiper_role.bump
+    )]
+    pub wiper_role: Account<'info, RoleState>,
+
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// mint的永久委托
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    /// 被清除的代币账户
+    #[account(
+        mut,
+        constraint = token_account.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"freeze", token_account.key().as_ref()],
+        bump,
+        constraint = freeze_state.is_frozen @ WusdError::AccountNotFrozen
+    )]
+    pub freeze_state: Account<'info, FreezeState>,
+
+    /// 国库账户，未提供时直接销毁余额
+    #[account(
+        mut,
+        constraint = treasury.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub treasury: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
 #[event]
 pub struct FreezeAccountEvent {
     pub authority: Pubkey,
@@ -138,4 +386,17 @@ pub struct UnfreezeAccountEvent {
     pub authority: Pubkey,
     pub freeze_state: Pubkey,
     pub timestamp: i64,
-}
\ No newline at end of file
+}
+
+/// 清除冻结账户余额事件
+#[event]
+pub struct WipeEvent {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// 接收余额的国库账户，为None表示已销毁
+    pub treasury: Option<Pubkey>,
+    pub timestamp: i64,
+}
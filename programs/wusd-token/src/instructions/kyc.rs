@@ -0,0 +1,221 @@
+use anchor_lang::prelude::*;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{KycRecord, KycTierLimits, RoleState};
+
+/// 创建或更新钱包的KYC记录
+/// * `ctx` - 上下文
+/// * `account` - 钱包地址
+/// * `tier` - KYC等级，须小于`KycRecord::TIER_COUNT`
+/// * `expires_at` - 过期时间，0表示永不过期
+/// * `jurisdiction` - 司法管辖区代码（ISO 3166-1 alpha-2，大写字母）
+pub fn set_kyc_record(
+    ctx: Context<SetKycRecord>,
+    account: Pubkey,
+    tier: u8,
+    expires_at: i64,
+    jurisdiction: [u8; 2],
+) -> Result<()> {
+    require!(
+        ctx.accounts.compliance_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(tier < KycRecord::TIER_COUNT, WusdError::InvalidKycTier);
+    require!(
+        jurisdiction.iter().all(u8::is_ascii_uppercase),
+        WusdError::InvalidJurisdiction
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at == 0 || expires_at > now, WusdError::InvalidExpiration);
+
+    let record = &mut ctx.accounts.kyc_record;
+    record.mint = ctx.accounts.token_mint.key();
+    record.account = account;
+    record.tier = tier;
+    record.expires_at = expires_at;
+    record.jurisdiction = jurisdiction;
+    record.updated_by = ctx.accounts.authority.key();
+    record.updated_at = now;
+    record.bump = *ctx.bumps.get("kyc_record").unwrap();
+
+    emit!(KycRecordUpdated {
+        mint: record.mint,
+        account,
+        tier,
+        expires_at,
+        jurisdiction,
+        authority: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// 删除钱包的KYC记录，该钱包回到未验证等级
+/// 每日流出累计保存在独立的`KycOutflow`账户中，不受删除影响
+/// * `ctx` - 上下文
+/// * `account` - 钱包地址
+pub fn remove_kyc_record(ctx: Context<RemoveKycRecord>, account: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.compliance_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    emit!(KycRecordRemoved {
+        mint: ctx.accounts.token_mint.key(),
+        account,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 设置KYC等级的限额，None表示不限制
+/// * `ctx` - 上下文
+/// * `tier` - KYC等级，须小于`KycRecord::TIER_COUNT`
+/// * `max_single_transfer` - 单笔转账上限
+/// * `max_daily_outflow` - 每日流出上限
+/// * `max_balance` - 接收代币账户的余额上限，按单个代币账户计算
+pub fn set_kyc_tier_limits(
+    ctx: Context<SetKycTierLimits>,
+    tier: u8,
+    max_single_transfer: Option<u64>,
+    max_daily_outflow: Option<u64>,
+    max_balance: Option<u64>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.compliance_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(tier < KycRecord::TIER_COUNT, WusdError::InvalidKycTier);
+
+    ctx.accounts.tier_limits.set_inner(KycTierLimits {
+        mint: ctx.accounts.token_mint.key(),
+        tier,
+        max_single_transfer,
+        max_daily_outflow,
+        max_balance,
+        bump: *ctx.bumps.get("tier_limits").unwrap(),
+    });
+
+    emit!(KycTierLimitsUpdated {
+        mint: ctx.accounts.token_mint.key(),
+        tier,
+        max_single_transfer,
+        max_daily_outflow,
+        max_balance,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(account: Pubkey)]
+pub struct SetKycRecord<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Compliance.seed()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Account<'info, RoleState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = KycRecord::SIZE,
+        seeds = [b"kyc", token_mint.key().as_ref(), account.as_ref()],
+        bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(account: Pubkey)]
+pub struct RemoveKycRecord<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Compliance.seed()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"kyc", token_mint.key().as_ref(), account.as_ref()],
+        bump = kyc_record.bump
+    )]
+    pub kyc_record: Account<'info, KycRecord>,
+}
+
+#[derive(Accounts)]
+#[instruction(tier: u8)]
+pub struct SetKycTierLimits<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Compliance.seed()],
+        bump = compliance_role.bump
+    )]
+    pub compliance_role: Account<'info, RoleState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = KycTierLimits::SIZE,
+        seeds = [b"kyc_tier", token_mint.key().as_ref(), &[tier]],
+        bump
+    )]
+    pub tier_limits: Account<'info, KycTierLimits>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// KYC记录更新事件
+#[event]
+pub struct KycRecordUpdated {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub tier: u8,
+    pub expires_at: i64,
+    pub jurisdiction: [u8; 2],
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// KYC记录删除事件
+#[event]
+pub struct KycRecordRemoved {
+    pub mint: Pubkey,
+    pub account: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// KYC等级限额更新事件
+#[event]
+pub struct KycTierLimitsUpdated {
+    pub mint: Pubkey,
+    pub tier: u8,
+    pub max_single_transfer: Option<u64>,
+    pub max_daily_outflow: Option<u64>,
+    pub max_balance: Option<u64>,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{memo_transfer::instruction as memo_transfer_instruction, ExtensionType},
+};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::TokenAccount;
+use crate::error::WusdError;
+
+/// 开启或关闭代币账户的入账备注要求（Token-2022 memo-transfer扩展）
+///
+/// 开启后，转入该账户的转账必须在同一交易中紧随备注指令，
+/// `transfer`/`transfer_from`附带备注时会自动满足该要求。
+/// * `ctx` - 上下文
+/// * `required` - 是否要求入账备注
+pub fn set_memo_required(ctx: Context<SetMemoRequired>, required: bool) -> Result<()> {
+    let token_program = ctx.accounts.token_program.key;
+    let account = ctx.accounts.token_account.to_account_info();
+    let owner = ctx.accounts.owner.to_account_info();
+
+    let ix = if required {
+        // 为账户追加memo-transfer扩展空间，已存在时不做改动
+        invoke(
+            &spl_token_2022::instruction::reallocate(
+                token_program,
+                account.key,
+                owner.key,
+                owner.key,
+                &[],
+                &[ExtensionType::MemoTransfer],
+            )?,
+            &[
+                account.clone(),
+                owner.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+        memo_transfer_instruction::enable_required_transfer_memos(
+            token_program,
+            account.key,
+            owner.key,
+            &[],
+        )?
+    } else {
+        memo_transfer_instruction::disable_required_transfer_memos(
+            token_program,
+            account.key,
+            owner.key,
+            &[],
+        )?
+    };
+    invoke(&ix, &[account, owner])?;
+
+    emit!(MemoRequiredUpdated {
+        token_account: ctx.accounts.token_account.key(),
+        owner: ctx.accounts.owner.key(),
+        required,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetMemoRequired<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ WusdError::InvalidOwner
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 入账备注要求变更事件
+#[event]
+pub struct MemoRequiredUpdated {
+    pub token_account: Pubkey,
+    pub owner: Pubkey,
+    pub required: bool,
+    pub timestamp: i64,
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::Mint;
+use crate::error::WusdError;
+use crate::state::AuthorityState;
+use crate::utils::top_up_rent;
+
+/// spl-token-metadata-interface Initialize指令鉴别符
+const TOKEN_METADATA_INITIALIZE: [u8; 8] = [210, 225, 30, 162, 88, 184, 77, 141];
+/// spl-token-metadata-interface UpdateField指令鉴别符
+const TOKEN_METADATA_UPDATE_FIELD: [u8; 8] = [221, 233, 49, 45, 181, 202, 220, 200];
+
+/// 名称最大长度
+pub const MAX_NAME_LEN: usize = 32;
+/// 符号最大长度
+pub const MAX_SYMBOL_LEN: usize = 10;
+/// URI及附加字段值的最大长度
+pub const MAX_URI_LEN: usize = 200;
+/// 附加字段键的最大长度
+pub const MAX_KEY_LEN: usize = 32;
+
+/// 元数据字段，序列化格式与spl-token-metadata-interface的`Field`一致
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum MetadataField {
+    /// 名称
+    Name,
+    /// 符号
+    Symbol,
+    /// URI
+    Uri,
+    /// 附加字段，如issuer、attestation_url
+    Key(String),
+}
+
+impl MetadataField {
+    /// 校验字段及其取值的长度
+    pub fn validate(&self, value: &str) -> Result<()> {
+        let max_len = match self {
+            MetadataField::Name => MAX_NAME_LEN,
+            MetadataField::Symbol => MAX_SYMBOL_LEN,
+            MetadataField::Uri => MAX_URI_LEN,
+            MetadataField::Key(key) => {
+                require!(
+                    !key.is_empty() && key.len() <= MAX_KEY_LEN,
+                    WusdError::MetadataTooLong
+                );
+                MAX_URI_LEN
+            }
+        };
+        require!(value.len() <= max_len, WusdError::MetadataTooLong);
+        Ok(())
+    }
+}
+
+/// 在mint中写入初始元数据，更新权限为authority_state PDA
+///
+/// # 参数
+/// * `mint` - 已启用元数据指针并完成InitializeMint的mint账户
+/// * `authority` - 铸币权限及元数据更新权限（authority_state PDA）
+/// * `signer_seeds` - authority_state PDA的签名种子
+pub fn initialize_token_metadata<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Result<()> {
+    MetadataField::Name.validate(&name)?;
+    MetadataField::Symbol.validate(&symbol)?;
+    MetadataField::Uri.validate(&uri)?;
+
+    let mut data = TOKEN_METADATA_INITIALIZE.to_vec();
+    (name, symbol, uri).serialize(&mut data)?;
+    let ix = Instruction {
+        program_id: *token_program.key,
+        accounts: vec![
+            AccountMeta::new(*mint.key, false),
+            AccountMeta::new_readonly(*authority.key, false),
+            AccountMeta::new_readonly(*mint.key, false),
+            AccountMeta::new_readonly(*authority.key, true),
+        ],
+        data,
+    };
+    invoke_signed(&ix, &[mint.clone(), authority.clone()], &[signer_seeds])?;
+    Ok(())
+}
+
+/// 更新代币元数据，仅管理员可调用
+/// * `ctx` - 上下文
+/// * `field` - 要更新的字段
+/// * `value` - 新的取值
+pub fn update_metadata(ctx: Context<UpdateMetadata>, field: MetadataField, value: String) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+    field.validate(&value)?;
+
+    let mut data = TOKEN_METADATA_UPDATE_FIELD.to_vec();
+    (&field, &value).serialize(&mut data)?;
+    let ix = Instruction {
+        program_id: ctx.accounts.token_program.key(),
+        accounts: vec![
+            AccountMeta::new(ctx.accounts.token_mint.key(), false),
+            AccountMeta::new_readonly(ctx.accounts.authority_state.key(), true),
+        ],
+        data,
+    };
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    invoke_signed(
+        &ix,
+        &[
+            ctx.accounts.token_mint.to_account_info(),
+            ctx.accounts.authority_state.to_account_info(),
+        ],
+        &[seeds],
+    )?;
+
+    // 元数据变长后由管理员补足mint的租金
+    top_up_rent(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.token_mint.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(MetadataUpdated {
+        mint: mint_key,
+        field,
+        value,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// 元数据存储在mint账户自身
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// 元数据更新权限
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 元数据更新事件
+#[event]
+pub struct MetadataUpdated {
+    pub mint: Pubkey,
+    pub field: MetadataField,
+    pub value: String,
+}
@@ -1,25 +1,66 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use crate::error::WusdError;   
 use anchor_spl::token_2022::Token2022;
 use anchor_spl::token_2022::{self, mint_to};
-use crate::utils::require_has_access;
-use crate::state::{AuthorityState, MintState, PauseState, AccessRegistryState};
+use crate::access::Role;
+use crate::utils::require_kyc_balance;
+use crate::state::{AuthorityState, MintState, RateLimit, PauseState, AccessRegistryState, MinterState, MinterRateLimit, RoleState};
 
-pub fn mint(ctx: Context<MintAccounts>, amount: u64, bump: u8) -> Result<()> {
-    // 验证Minter权限 
+/// 链下参考编号（如法币入金编号）的最大长度
+pub const MAX_REFERENCE_LEN: usize = 64;
+
+/// 铸造WUSD代币
+/// * `ctx` - 铸币上下文
+/// * `amount` - 铸币数量
+/// * `bump` - authority_state PDA bump
+/// * `reference` - 可选的链下参考编号，记录在铸币事件中
+pub fn mint(ctx: Context<MintAccounts>, amount: u64, bump: u8, reference: Option<String>) -> Result<()> {
+    if let Some(reference) = &reference {
+        require!(reference.len() <= MAX_REFERENCE_LEN, WusdError::ReferenceTooLong);
+    }
+
+    // 验证Minter角色及铸币者配置
+    require!(
+        ctx.accounts.minter_role.has_role(ctx.accounts.authority.key()),
+        WusdError::NotMinter
+    );
     require!(
-        ctx.accounts.authority_state.is_minter(ctx.accounts.authority.key()), 
+        ctx.accounts.minter_state.minter == ctx.accounts.authority.key(), 
         WusdError::NotMinter
     );
-    // 验证访问权限
-    require_has_access(
-        ctx.accounts.authority.key(),
-        false,
-        Some(amount),
-        &ctx.accounts.pause_state,
-        Some(&ctx.accounts.access_registry),
+    // 验证合约未暂停；铸币权限由Minter角色及铸币额度决定，铸币者无需是操作员
+    ctx.accounts.pause_state.validate_not_paused()?;
+    require!(amount > 0, WusdError::InvalidAmount);
+
+    // 验证接收者KYC等级的余额上限
+    require_kyc_balance(
+        &ctx.accounts.token_mint.key(),
+        &ctx.accounts.recipient_kyc,
+        &ctx.accounts.recipient_kyc_limits,
+        ctx.accounts
+            .token_account
+            .amount
+            .checked_add(amount)
+            .ok_or(WusdError::InvalidAmount)?,
     )?;
 
+    // 验证供应量上限
+    ctx.accounts.mint_state.validate_supply(ctx.accounts.token_mint.supply, amount)?;
+
+    // 扣减铸币者额度
+    ctx.accounts.minter_state.consume_allowance(amount)?;
+
+    // 检查铸币者及全局的铸币速率限制
+    let now = Clock::get()?.unix_timestamp;
+    let minter_rate_limit = &ctx.accounts.minter_rate_limit;
+    if !minter_rate_limit.data_is_empty() {
+        let mut state = MinterRateLimit::try_deserialize(&mut &minter_rate_limit.try_borrow_data()?[..])?;
+        state.rate_limit.consume(amount, now)?;
+        state.try_serialize(&mut &mut minter_rate_limit.try_borrow_mut_data()?[..])?;
+    }
+    ctx.accounts.mint_state.mint_rate_limit.consume(amount, now)?;
+
     // 执行铸币
     let mint_key = ctx.accounts.token_mint.key();
     let seeds = &[b"authority", mint_key.as_ref(), &[bump]];
@@ -36,6 +77,17 @@ pub fn mint(ctx: Context<MintAccounts>, amount: u64, bump: u8) -> Result<()> {
         amount
     )?;
 
+    // 发出铸币事件
+    ctx.accounts.token_mint.reload()?;
+    emit!(MintEvent {
+        minter: ctx.accounts.authority.key(),
+        recipient: ctx.accounts.token_account.key(),
+        amount,
+        total_supply: ctx.accounts.token_mint.supply,
+        timestamp: now,
+        reference,
+    });
+
     Ok(())
 }
 
@@ -51,9 +103,289 @@ pub struct MintAccounts<'info> {
     pub token_program: Program<'info, Token2022>,
     #[account(mut)]
     pub authority_state: Account<'info, AuthorityState>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"mint_state", token_mint.key().as_ref()],
+        bump,
+        constraint = mint_state.mint == token_mint.key() @ WusdError::InvalidMint,
+        constraint = mint_state.decimals == token_mint.decimals @ WusdError::InvalidDecimals
+    )]
     pub mint_state: Account<'info, MintState>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"pause_state", token_mint.key().as_ref()],
+        bump,
+    )]
     pub pause_state: Account<'info, PauseState>,
+    #[account(
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
     pub access_registry: Account<'info, AccessRegistryState>,
-}
\ No newline at end of file
+    #[account(
+        mut,
+        seeds = [b"minter", token_mint.key().as_ref(), authority.key().as_ref()],
+        bump = minter_state.bump
+    )]
+    pub minter_state: Account<'info, MinterState>,
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Minter.seed()],
+        bump = minter_role.bump
+    )]
+    pub minter_role: Box<Account<'info, RoleState>>,
+    /// CHECK: 铸币者的速率限制PDA，账户不存在表示未设置限制
+    #[account(
+        mut,
+        seeds = [b"minter_rate_limit", token_mint.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub minter_rate_limit: AccountInfo<'info>,
+    /// CHECK: 接收者的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), token_account.owner.as_ref()],
+        bump,
+        constraint = recipient_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub recipient_blacklist: AccountInfo<'info>,
+    /// CHECK: 接收者的白名单条目PDA，仅白名单模式下要求账户存在
+    #[account(
+        seeds = [b"allowlist", token_mint.key().as_ref(), token_account.owner.as_ref()],
+        bump,
+        constraint = !access_registry.allowlist_enabled || !recipient_allowlist.data_is_empty()
+            @ WusdError::RecipientNotAllowlisted
+    )]
+    pub recipient_allowlist: AccountInfo<'info>,
+    /// CHECK: 接收者的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", token_mint.key().as_ref(), token_account.owner.as_ref()],
+        bump
+    )]
+    pub recipient_kyc: AccountInfo<'info>,
+    /// CHECK: 接收者所在KYC等级的限额PDA，在`require_kyc_balance`中校验地址
+    pub recipient_kyc_limits: AccountInfo<'info>,
+}
+
+/// 设置最大供应量，仅管理员可调用
+/// * `ctx` - 上下文
+/// * `max_supply` - 新的最大供应量
+pub fn set_max_supply(ctx: Context<UpdateMintConfig>, max_supply: u64) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    let mint_state = &mut ctx.accounts.mint_state;
+    let previous_max_supply = mint_state.max_supply;
+    mint_state.max_supply = max_supply;
+
+    emit!(MaxSupplyUpdated {
+        mint: ctx.accounts.token_mint.key(),
+        previous_max_supply,
+        max_supply,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintConfig<'info> {
+    pub authority: Signer<'info>,
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+    #[account(
+        mut,
+        seeds = [b"mint_state", token_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_state: Account<'info, MintState>,
+}
+
+/// 最大供应量变更事件
+#[event]
+pub struct MaxSupplyUpdated {
+    pub mint: Pubkey,
+    pub previous_max_supply: u64,
+    pub max_supply: u64,
+}
+
+/// 设置全局铸币速率限制，仅管理员可调用
+/// * `ctx` - 上下文
+/// * `limit` - 窗口内允许铸造的最大数量，0表示不限制
+/// * `window` - 滑动窗口长度（秒），须为24的整数倍
+pub fn set_mint_rate_limit(ctx: Context<UpdateMintConfig>, limit: u64, window: i64) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    ctx.accounts.mint_state.mint_rate_limit.configure(limit, window)?;
+
+    emit!(MintRateLimitUpdated {
+        mint: ctx.accounts.token_mint.key(),
+        minter: None,
+        limit,
+        window,
+    });
+
+    Ok(())
+}
+
+/// 设置单个铸币者的铸币速率限制，仅管理员可调用
+///
+/// 限制保存在独立的PDA中，移除铸币者后仍然保留
+/// * `ctx` - 上下文
+/// * `minter` - 铸币者地址
+/// * `limit` - 窗口内允许铸造的最大数量，0表示不限制
+/// * `window` - 滑动窗口长度（秒），须为24的整数倍
+pub fn set_minter_rate_limit(
+    ctx: Context<SetMinterRateLimit>,
+    minter: Pubkey,
+    limit: u64,
+    window: i64,
+) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    let minter_rate_limit = &mut ctx.accounts.minter_rate_limit;
+    minter_rate_limit.mint = ctx.accounts.token_mint.key();
+    minter_rate_limit.minter = minter;
+    minter_rate_limit.bump = *ctx.bumps.get("minter_rate_limit").unwrap();
+    minter_rate_limit.rate_limit.configure(limit, window)?;
+
+    emit!(MintRateLimitUpdated {
+        mint: ctx.accounts.token_mint.key(),
+        minter: Some(minter),
+        limit,
+        window,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct SetMinterRateLimit<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MinterRateLimit::SIZE,
+        seeds = [b"minter_rate_limit", token_mint.key().as_ref(), minter.as_ref()],
+        bump
+    )]
+    pub minter_rate_limit: Account<'info, MinterRateLimit>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 将旧版铸币状态账户（mint/decimals）迁移为当前布局
+///
+/// 账户扩容到`MintState::SIZE`，补足的租金由调用者支付。
+/// 迁移后最大供应量不设上限，全局铸币速率限制为0即不限制，与`initialize`的默认值一致。
+/// * `ctx` - 上下文
+pub fn migrate_mint_state(ctx: Context<MigrateMintState>) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+
+    let mint_state_info = ctx.accounts.mint_state.to_account_info();
+    let (mint, decimals) = {
+        let data = mint_state_info.try_borrow_data()?;
+        require!(
+            data.len() == MintState::LEGACY_DATA_LEN
+                && data[..8] == MintState::DISCRIMINATOR,
+            WusdError::AlreadyMigrated
+        );
+        (Pubkey::try_from(&data[8..40]).unwrap(), data[40])
+    };
+    require!(mint == ctx.accounts.token_mint.key(), WusdError::InvalidMint);
+
+    // 扩容账户并写入新布局
+    mint_state_info.realloc(MintState::SIZE, true)?;
+    crate::utils::top_up_rent(
+        &ctx.accounts.authority.to_account_info(),
+        &mint_state_info,
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+    MintState {
+        mint,
+        decimals,
+        max_supply: u64::MAX,
+        mint_rate_limit: RateLimit::default(),
+    }
+    .try_serialize(&mut &mut mint_state_info.try_borrow_mut_data()?[..])?;
+
+    emit!(MintStateMigrated {
+        mint,
+        decimals,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateMintState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+    /// CHECK: 旧版铸币状态账户，布局与当前`MintState`不同，在指令中手动解析
+    #[account(
+        mut,
+        seeds = [b"mint_state", token_mint.key().as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub mint_state: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// 铸币状态账户迁移事件
+#[event]
+pub struct MintStateMigrated {
+    pub mint: Pubkey,
+    pub decimals: u8,
+}
+
+/// 铸币速率限制变更事件，`minter`为None时表示全局限制
+#[event]
+pub struct MintRateLimitUpdated {
+    pub mint: Pubkey,
+    pub minter: Option<Pubkey>,
+    pub limit: u64,
+    pub window: i64,
+}
+
+/// 铸币事件，记录每次铸币的详细信息，用于对账
+#[event]
+pub struct MintEvent {
+    /// 铸币者地址
+    pub minter: Pubkey,
+    /// 接收代币的代币账户
+    pub recipient: Pubkey,
+    /// 铸币数量
+    pub amount: u64,
+    /// 铸币后的总供应量
+    pub total_supply: u64,
+    /// 铸币时间
+    pub timestamp: i64,
+    /// 链下参考编号
+    pub reference: Option<String>,
+}
@@ -0,0 +1,198 @@
+use anchor_lang::prelude::*;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::instructions::role::{RoleGranted, RoleRevoked};
+use crate::state::{MinterState, RoleState};
+
+/// 配置铸币者及其铸币额度，已存在的铸币者额度将被覆盖
+///
+/// 同时将铸币者加入Minter角色，`mint`要求调用者同时持有该角色和额度
+/// * `ctx` - 上下文
+/// * `minter` - 铸币者地址
+/// * `allowance` - 铸币额度
+pub fn configure_minter(ctx: Context<ConfigureMinter>, minter: Pubkey, allowance: u64) -> Result<()> {
+    require!(
+        ctx.accounts.master_minter_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    let minter_state = &mut ctx.accounts.minter_state;
+    minter_state.mint = ctx.accounts.token_mint.key();
+    minter_state.minter = minter;
+    minter_state.allowance = allowance;
+    minter_state.bump = *ctx.bumps.get("minter_state").unwrap();
+
+    let minter_role = &mut ctx.accounts.minter_role;
+    if minter_role.is_uninitialized() {
+        minter_role.mint = ctx.accounts.token_mint.key();
+        minter_role.role = Role::Minter;
+        minter_role.bump = *ctx.bumps.get("minter_role").unwrap();
+    }
+    if minter_role.grant(minter)? {
+        emit!(RoleGranted {
+            mint: ctx.accounts.token_mint.key(),
+            role: Role::Minter,
+            member: minter,
+            sender: ctx.accounts.authority.key(),
+        });
+    }
+
+    emit!(MinterConfigured {
+        mint: ctx.accounts.token_mint.key(),
+        minter,
+        allowance,
+    });
+
+    Ok(())
+}
+
+/// 增加铸币者的铸币额度
+/// * `ctx` - 上下文
+/// * `minter` - 铸币者地址
+/// * `amount` - 增加的额度
+pub fn increment_minter_allowance(ctx: Context<UpdateMinter>, minter: Pubkey, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.master_minter_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(amount > 0, WusdError::InvalidAmount);
+
+    let minter_state = &mut ctx.accounts.minter_state;
+    minter_state.increase_allowance(amount)?;
+
+    emit!(MinterConfigured {
+        mint: ctx.accounts.token_mint.key(),
+        minter,
+        allowance: minter_state.allowance,
+    });
+
+    Ok(())
+}
+
+/// 移除铸币者，关闭其状态账户并撤销其Minter角色
+/// * `ctx` - 上下文
+/// * `minter` - 铸币者地址
+pub fn remove_minter(ctx: Context<RemoveMinter>, minter: Pubkey) -> Result<()> {
+    require!(
+        ctx.accounts.master_minter_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+
+    if ctx.accounts.minter_role.has_role(minter) {
+        ctx.accounts.minter_role.revoke(minter)?;
+        emit!(RoleRevoked {
+            mint: ctx.accounts.token_mint.key(),
+            role: Role::Minter,
+            member: minter,
+            sender: ctx.accounts.authority.key(),
+        });
+    }
+
+    emit!(MinterRemoved {
+        mint: ctx.accounts.token_mint.key(),
+        minter,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct ConfigureMinter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::MasterMinter.seed()],
+        bump = master_minter_role.bump
+    )]
+    pub master_minter_role: Account<'info, RoleState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MinterState::SIZE,
+        seeds = [b"minter", token_mint.key().as_ref(), minter.as_ref()],
+        bump
+    )]
+    pub minter_state: Account<'info, MinterState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RoleState::SIZE,
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Minter.seed()],
+        bump
+    )]
+    pub minter_role: Account<'info, RoleState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct UpdateMinter<'info> {
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::MasterMinter.seed()],
+        bump = master_minter_role.bump
+    )]
+    pub master_minter_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        seeds = [b"minter", token_mint.key().as_ref(), minter.as_ref()],
+        bump = minter_state.bump
+    )]
+    pub minter_state: Account<'info, MinterState>,
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct RemoveMinter<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::MasterMinter.seed()],
+        bump = master_minter_role.bump
+    )]
+    pub master_minter_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"minter", token_mint.key().as_ref(), minter.as_ref()],
+        bump = minter_state.bump
+    )]
+    pub minter_state: Account<'info, MinterState>,
+
+    #[account(
+        mut,
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Minter.seed()],
+        bump = minter_role.bump
+    )]
+    pub minter_role: Account<'info, RoleState>,
+}
+
+/// 铸币者配置事件，记录铸币者当前的铸币额度
+#[event]
+pub struct MinterConfigured {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+    pub allowance: u64,
+}
+
+/// 铸币者移除事件
+#[event]
+pub struct MinterRemoved {
+    pub mint: Pubkey,
+    pub minter: Pubkey,
+}
@@ -2,6 +2,18 @@ pub mod mint;
 pub mod burn;
 pub mod transfer;
 pub mod permit;
+pub mod allowance;
 pub mod operator;
 pub mod pause;
-pub mod freeze;
\ No newline at end of file
+pub mod freeze;
+pub mod role;
+pub mod admin;
+pub mod minter;
+pub mod redemption;
+pub mod blacklist;
+pub mod metadata;
+pub mod fee;
+pub mod memo;
+pub mod allowlist;
+pub mod kyc;
+pub mod rescue;
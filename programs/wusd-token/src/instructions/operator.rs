@@ -1,50 +1,361 @@
-use anchor_lang::prelude::*; 
-use crate::error::WusdError;  
-use crate::state::{AuthorityState, AccessRegistryState};
- 
-/// 添加操作员
-pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::error::WusdError;
+use crate::access::{Permission, Role};
+use crate::state::{AccessRegistryState, AuthorityState, OperatorRecord, RoleState};
+use crate::utils::{close_pda_account, create_pda_account};
+
+/// 添加操作员，为其创建操作员记录PDA
+/// * `operator` - 操作员地址
+/// * `label` - 备注名称
+/// * `expires_at` - 过期时间，0表示永不过期
+/// * `permissions` - 权限位，见`Permission`
+pub fn add_operator(
+    ctx: Context<AddOperator>,
+    operator: Pubkey,
+    label: String,
+    expires_at: i64,
+    permissions: u32,
+) -> Result<()> {
     let access_registry = &mut ctx.accounts.access_registry;
     require!(access_registry.initialized, WusdError::AccessRegistryNotInitialized);
-    
-    // 确保调用者是管理员
+
+    // 确保调用者是操作员管理者
     require!(
-        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
-        WusdError::Unauthorized
-    ); 
-    
+        ctx.accounts.operator_admin_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(
+        label.len() <= OperatorRecord::MAX_LABEL_LEN,
+        WusdError::OperatorLabelTooLong
+    );
+    require!(Permission::is_valid(permissions), WusdError::InvalidPermissions);
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at == 0 || expires_at > now, WusdError::InvalidExpiration);
+
     // 添加操作员
-    access_registry.add_operator(operator) 
-}  
+    ctx.accounts.operator_record.set_inner(OperatorRecord {
+        mint: access_registry.mint,
+        operator,
+        label: label.clone(),
+        added_at: now,
+        added_by: ctx.accounts.authority.key(),
+        expires_at,
+        permissions,
+        bump: *ctx.bumps.get("operator_record").unwrap(),
+    });
+    access_registry.operator_count = access_registry
+        .operator_count
+        .checked_add(1)
+        .ok_or(WusdError::TooManyOperators)?;
+
+    emit!(OperatorAdded {
+        mint: access_registry.mint,
+        operator,
+        label,
+        expires_at,
+        permissions,
+        added_by: ctx.accounts.authority.key(),
+        timestamp: now,
+    });
+
+    Ok(())
+}
 
-/// 移除操作员
-pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+/// 更新操作员的权限位
+/// * `operator` - 操作员地址
+/// * `permissions` - 新的权限位，见`Permission`
+pub fn update_operator_permissions(
+    ctx: Context<UpdateOperatorPermissions>,
+    operator: Pubkey,
+    permissions: u32,
+) -> Result<()> {
+    // 确保调用者是操作员管理者
+    require!(
+        ctx.accounts.operator_admin_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(Permission::is_valid(permissions), WusdError::InvalidPermissions);
+
+    let record = &mut ctx.accounts.operator_record;
+    let previous_permissions = record.permissions;
+    record.permissions = permissions;
+
+    emit!(OperatorPermissionsUpdated {
+        mint: record.mint,
+        operator,
+        previous_permissions,
+        permissions,
+        updated_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 移除操作员，关闭其操作员记录PDA
+pub fn remove_operator(ctx: Context<RemoveOperator>, operator: Pubkey) -> Result<()> {
     let access_registry = &mut ctx.accounts.access_registry;
     require!(access_registry.initialized, WusdError::AccessRegistryNotInitialized);
-    
-    // 确保调用者是管理员
+
+    // 确保调用者是操作员管理者
     require!(
-        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
-        WusdError::Unauthorized
+        ctx.accounts.operator_admin_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
     );
-    
+
     // 移除操作员
-    access_registry.remove_operator(operator)
+    access_registry.operator_count = access_registry.operator_count.saturating_sub(1);
+
+    emit!(OperatorRemoved {
+        mint: access_registry.mint,
+        operator,
+        removed_by: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+/// 将旧版全局注册表（种子为`[b"access_registry"]`）迁移为指定mint的注册表
+///
+/// 为旧注册表中的每个操作员创建操作员记录后关闭旧账户，租金退还给调用者。
+/// 迁移的操作员保留旧版的全部扣款权限（转账、授权转账、销毁）。
+/// 调用者须同时是旧注册表的管理员和该mint的管理员。
+/// * `ctx` - 上下文，`remaining_accounts`按旧注册表中的顺序传入各操作员记录PDA（可写）
+pub fn migrate_access_registry<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateAccessRegistry<'info>>,
+) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_registry.to_account_info();
+    let (authority, operators, operator_count) = {
+        let data = legacy_info.try_borrow_data()?;
+        require!(
+            data.len() >= AccessRegistryState::LEGACY_DATA_LEN
+                && data[..8] == AccessRegistryState::DISCRIMINATOR,
+            WusdError::AccessRegistryNotInitialized
+        );
+        let authority = Pubkey::try_from(&data[8..40]).unwrap();
+        let initialized = data[40] != 0;
+        require!(initialized, WusdError::AccessRegistryNotInitialized);
+        let mut operators = [Pubkey::default(); 10];
+        for (i, operator) in operators.iter_mut().enumerate() {
+            let offset = 41 + i * 32;
+            *operator = Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+        }
+        (authority, operators, data[41 + 32 * 10])
+    };
+    require!(
+        authority == ctx.accounts.authority.key(),
+        WusdError::Unauthorized
+    );
+    require!(
+        ctx.remaining_accounts.len() == operator_count as usize,
+        WusdError::InvalidOperatorRecord
+    );
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+    for (operator, record_info) in operators.iter().zip(ctx.remaining_accounts.iter()) {
+        let (expected, bump) = Pubkey::find_program_address(
+            &[b"operator", mint_key.as_ref(), operator.as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(record_info.key(), expected, WusdError::InvalidOperatorRecord);
+
+        create_pda_account(
+            &ctx.accounts.authority.to_account_info(),
+            record_info,
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.program_id,
+            OperatorRecord::SIZE,
+            &[b"operator", mint_key.as_ref(), operator.as_ref(), &[bump]],
+        )?;
+
+        let record = OperatorRecord {
+            mint: mint_key,
+            operator: *operator,
+            label: String::new(),
+            added_at: now,
+            added_by: authority,
+            expires_at: 0,
+            permissions: Permission::CAN_TRANSFER
+                | Permission::CAN_TRANSFER_FROM
+                | Permission::CAN_BURN,
+            bump,
+        };
+        record.try_serialize(&mut &mut record_info.try_borrow_mut_data()?[..])?;
+    }
+
+    let access_registry = &mut ctx.accounts.access_registry;
+    access_registry.authority = authority;
+    access_registry.mint = mint_key;
+    access_registry.operator_count = operator_count as u64;
+    access_registry.initialized = true;
+    access_registry.allowlist_enabled = false;
+
+    // 关闭旧注册表
+    close_pda_account(&legacy_info, &ctx.accounts.authority.to_account_info())?;
+
+    emit!(AccessRegistryMigrated {
+        mint: mint_key,
+        legacy_registry: legacy_info.key(),
+        access_registry: ctx.accounts.access_registry.key(),
+        operator_count,
+    });
+
+    Ok(())
 }
 
 #[derive(Accounts)]
-pub struct ManageOperator<'info> {
+#[instruction(operator: Pubkey)]
+pub struct AddOperator<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
 
-    #[account(constraint = authority_state.is_admin(authority.key()))]
-    pub authority_state: Account<'info, AuthorityState>,
+    #[account(
+        seeds = [b"role", operator_admin_role.mint.as_ref(), &Role::OperatorAdmin.seed()],
+        bump = operator_admin_role.bump
+    )]
+    pub operator_admin_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        seeds = [b"access_registry", operator_admin_role.mint.as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = OperatorRecord::SIZE,
+        seeds = [b"operator", operator_admin_role.mint.as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct UpdateOperatorPermissions<'info> {
+    pub authority: Signer<'info>,
 
-    /// CHECK: 仅用于记录地址
-    pub operator: AccountInfo<'info>,
+    #[account(
+        seeds = [b"role", operator_admin_role.mint.as_ref(), &Role::OperatorAdmin.seed()],
+        bump = operator_admin_role.bump
+    )]
+    pub operator_admin_role: Account<'info, RoleState>,
 
+    #[account(
+        mut,
+        seeds = [b"operator", operator_admin_role.mint.as_ref(), operator.as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+}
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct RemoveOperator<'info> {
     #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", operator_admin_role.mint.as_ref(), &Role::OperatorAdmin.seed()],
+        bump = operator_admin_role.bump
+    )]
+    pub operator_admin_role: Account<'info, RoleState>,
+
+    #[account(
+        mut,
+        seeds = [b"access_registry", operator_admin_role.mint.as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"operator", operator_admin_role.mint.as_ref(), operator.as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Account<'info, OperatorRecord>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateAccessRegistry<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump,
+        constraint = authority_state.admin == authority.key() @ WusdError::Unauthorized
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    /// CHECK: 旧版全局注册表，布局与当前`AccessRegistryState`不同，在指令中手动解析
+    #[account(
+        mut,
+        seeds = [b"access_registry"],
+        bump,
+        owner = crate::ID
+    )]
+    pub legacy_registry: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AccessRegistryState::SIZE,
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump
+    )]
     pub access_registry: Account<'info, AccessRegistryState>,
 
     pub system_program: Program<'info, System>,
-} 
\ No newline at end of file
+}
+
+/// 添加操作员事件
+#[event]
+pub struct OperatorAdded {
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub label: String,
+    pub expires_at: i64,
+    pub permissions: u32,
+    pub added_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 操作员权限更新事件
+#[event]
+pub struct OperatorPermissionsUpdated {
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub previous_permissions: u32,
+    pub permissions: u32,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 移除操作员事件
+#[event]
+pub struct OperatorRemoved {
+    pub mint: Pubkey,
+    pub operator: Pubkey,
+    pub removed_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// 注册表迁移事件
+#[event]
+pub struct AccessRegistryMigrated {
+    pub mint: Pubkey,
+    pub legacy_registry: Pubkey,
+    pub access_registry: Pubkey,
+    pub operator_count: u8,
+}
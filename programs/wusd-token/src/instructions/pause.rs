@@ -1,41 +1,85 @@
 use anchor_lang::prelude::*;
 use crate::error::WusdError;  
-use crate::state::{AuthorityState, PauseState};
+use crate::access::Role;
+use crate::state::{AuthorityState, PauseState, RoleState};
 
-/// 暂停合约
+/// 暂停合约，管理员或Pauser角色成员可调用
 /// * `ctx` - 上下文
 pub fn pause(ctx: Context<Pause>) -> Result<()> {
     require!(
-        ctx.accounts.authority_state.is_pauser(ctx.accounts.authority.key()),
+        is_pauser(
+            &ctx.accounts.authority_state,
+            ctx.accounts.pauser_role.as_deref(),
+            ctx.accounts.authority.key(),
+        ),
         WusdError::NotPauser
     );
     ctx.accounts.pause_state.set_paused(true);
     Ok(())
 }
 
-/// 恢复合约
+/// 恢复合约，管理员或Pauser角色成员可调用
 /// * `ctx` - 上下文
 pub fn unpause(ctx: Context<Unpause>) -> Result<()> {
     require!(
-        ctx.accounts.authority_state.is_pauser(ctx.accounts.authority.key()),
+        is_pauser(
+            &ctx.accounts.authority_state,
+            ctx.accounts.pauser_role.as_deref(),
+            ctx.accounts.authority.key(),
+        ),
         WusdError::NotPauser
     );
     ctx.accounts.pause_state.set_paused(false);
     Ok(())
 }
 
+/// 调用者是否为管理员或Pauser角色成员
+fn is_pauser(authority_state: &AuthorityState, pauser_role: Option<&RoleState>, user: Pubkey) -> bool {
+    authority_state.is_admin(user) || pauser_role.is_some_and(|role| role.has_role(user))
+}
+
 #[derive(Accounts)]
 pub struct Pause<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"pause_state", token_mint.key().as_ref()],
+        bump
+    )]
     pub pause_state: Account<'info, PauseState>,
     pub authority: Signer<'info>,
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
     pub authority_state: Account<'info, AuthorityState>,
+    /// Pauser角色账户，管理员调用时可传入None
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Pauser.seed()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleState>>,
 }
 
 #[derive(Accounts)]
 pub struct Unpause<'info> {
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"pause_state", token_mint.key().as_ref()],
+        bump
+    )]
     pub pause_state: Account<'info, PauseState>,
     pub authority: Signer<'info>,
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
     pub authority_state: Account<'info, AuthorityState>,
+    /// Pauser角色账户，管理员调用时可传入None
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Pauser.seed()],
+        bump = pauser_role.bump
+    )]
+    pub pauser_role: Option<Account<'info, RoleState>>,
 }
\ No newline at end of file
@@ -1,7 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar;
 use anchor_spl::token_2022::Token2022; 
 use crate::error::WusdError;  
-use crate::state::{MintState, PermitState, AllowanceState};
+use crate::access::Permission;
+use crate::state::{AccessRegistryState, MintState, AllowanceState, OperatorRecord, PermitScope};
+use crate::instructions::allowance::ApprovalEvent;
+use crate::utils::verify_ed25519_instruction;
+
+/// 许可签名域名前缀
+pub const PERMIT_DOMAIN: &[u8] = b"WUSD Permit";
+/// 许可签名版本
+pub const PERMIT_VERSION: &[u8] = b"1";
+/// Solana没有EVM意义上的链ID，固定值仅用于与其他链版本的消息格式对齐
+pub const PERMIT_CHAIN_ID: u64 = 0;
 
 /// 处理授权许可请求，允许代币持有者授权其他账户使用其代币
 /// 
@@ -12,86 +24,204 @@ use crate::state::{MintState, PermitState, AllowanceState};
 /// # 返回值
 /// * `Result<()>` - 操作成功返回Ok(()), 失败返回错误
 pub fn permit(ctx: Context<Permit>, params: PermitParams) -> Result<()> { 
+    // 代他人提交时验证中继者权限
+    if ctx.accounts.relayer.key() != ctx.accounts.owner.key() {
+        require!(
+            ctx.accounts.access_registry.has_access(
+                ctx.accounts.relayer.key(),
+                Permission::CAN_RELAY_PERMIT,
+                ctx.accounts.operator_record.as_deref(),
+                ctx.accounts.clock.unix_timestamp,
+            ),
+            WusdError::AccessDenied
+        );
+    }
+
     // 验证基本参数
     require!(params.amount > 0, WusdError::InvalidAmount);
-    
-    // 初始化 permit_state
-    ctx.accounts.permit_state.set_inner(PermitState::initialize(
+    require!(params.scope.is_valid(), WusdError::InvalidPermitScope);
+
+    // 验证许可未过期
+    require!(
+        params.deadline > ctx.accounts.clock.unix_timestamp,
+        WusdError::PermitExpired
+    );
+
+    // 签名公钥必须是owner
+    require!(
+        Pubkey::new_from_array(params.public_key) == ctx.accounts.owner.key(),
+        WusdError::InvalidSignature
+    );
+
+    // 首次使用时初始化授权账户，已存在的授权保留随机数
+    let allowance = &mut ctx.accounts.allowance;
+    if allowance.is_uninitialized() {
+        allowance.set_inner(AllowanceState::initialize(
+            ctx.accounts.mint_state.mint,
+            ctx.accounts.owner.key(),
+            ctx.accounts.spender.key(),
+            *ctx.bumps.get("allowance").unwrap()
+        ));
+    }
+
+    // 验证随机数，防止签名重放
+    let nonce = params.nonce.unwrap_or_default();
+    allowance.validate_nonce(nonce)?;
+
+    // 重建许可消息，并校验交易中的Ed25519指令对其签名
+    let message = PermitMessage::new(
+        ctx.program_id,
+        &ctx.accounts.mint_state.mint,
         ctx.accounts.owner.key(),
         ctx.accounts.spender.key(),
-        params.amount,
-        params.deadline,
-        *ctx.bumps.get("permit_state").unwrap()
-    ));
+        &params,
+    );
+    verify_ed25519_instruction(
+        &ctx.accounts.instructions,
+        &ctx.accounts.owner.key(),
+        &message.try_to_vec()?,
+        &params.signature,
+    )?;
     
-    // 设置授权额度
-    ctx.accounts.allowance.amount = params.amount;
+    // 写入授权额度并消耗随机数
+    let allowance = &mut ctx.accounts.allowance;
+    allowance.amount = params.amount;
+    allowance.expiration = params.deadline;
+    allowance.scope = params.scope;
+    allowance.increment_nonce();
     
     // 发出授权许可事件
     emit!(PermitGranted { 
         owner: ctx.accounts.owner.key(),
         spender: ctx.accounts.spender.key(),
         amount: params.amount,
-        scope: PermitScope::TRANSFER
+        scope: params.scope
+    });
+    emit!(ApprovalEvent {
+        mint: ctx.accounts.mint_state.mint,
+        owner: ctx.accounts.owner.key(),
+        spender: ctx.accounts.spender.key(),
+        amount: params.amount,
     });
     
     Ok(())
 }
- 
-/// 许可授权范围枚举
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
-pub struct PermitScope {
-    /// 单次授权
-    pub one_time: bool,
-    /// 永久授权
-    pub permanent: bool,
-    /// 转账授权
-    pub transfer: bool,
-    /// 销毁授权
-    pub burn: bool,
-    /// 全部授权
-    pub all: bool
-}
 
+/// 作废owner对某个spender尚未提交的许可签名
+///
+/// # 参数
+/// * `ctx` - 包含所有必要账户的上下文
+/// * `new_nonce` - 新的随机数，所有小于该值的许可签名都将失效
+pub fn invalidate_nonces(ctx: Context<InvalidateNonces>, new_nonce: u64) -> Result<()> {
+    let allowance = &mut ctx.accounts.allowance;
+    if allowance.is_uninitialized() {
+        allowance.set_inner(AllowanceState::initialize(
+            ctx.accounts.token_mint.key(),
+            ctx.accounts.owner.key(),
+            ctx.accounts.spender.key(),
+            *ctx.bumps.get("allowance").unwrap()
+        ));
+    }
+
+    allowance.invalidate_nonces(new_nonce)?;
+
+    emit!(NoncesInvalidated {
+        owner: ctx.accounts.owner.key(),
+        spender: ctx.accounts.spender.key(),
+        nonce: new_nonce,
+    });
+
+    Ok(())
+}
+ 
 #[derive(Accounts)]
 #[instruction(params: PermitParams)]
 pub struct Permit<'info> {
+    /// 提交交易并支付费用的中继者，不是owner时须拥有`CAN_RELAY_PERMIT`权限
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub relayer: Signer<'info>,
+
+    /// CHECK: owner无需签署交易，其授权由Ed25519签名指令证明
+    pub owner: AccountInfo<'info>,
 
     /// CHECK: This is the spender account that will be granted permission
     pub spender: AccountInfo<'info>,
 
     #[account(
         init_if_needed,
-        payer = owner,
+        payer = relayer,
         space = AllowanceState::SIZE,
-        seeds = [b"allowance", owner.key().as_ref(), spender.key().as_ref()],
+        seeds = [b"allowance", mint_state.mint.as_ref(), owner.key().as_ref(), spender.key().as_ref()],
         bump
     )]
     pub allowance: Account<'info, AllowanceState>,
 
     #[account(
-        init_if_needed,
-        payer = owner,
-        space = PermitState::SIZE,
-        seeds = [
-            b"permit",
-            owner.key().as_ref(),
-            spender.key().as_ref()
-        ],
+        seeds = [b"mint_state", mint_state.mint.as_ref()],
+        bump
+    )]
+    pub mint_state: Box<Account<'info, MintState>>,
+
+    /// CHECK: owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint_state.mint.as_ref(), owner.key().as_ref()],
         bump,
+        constraint = owner_blacklist.data_is_empty() @ WusdError::Blacklisted
     )]
-    pub permit_state: Account<'info, PermitState>,
+    pub owner_blacklist: AccountInfo<'info>,
 
-    #[account(mut)]
-    pub mint_state: Box<Account<'info, MintState>>,
+    /// CHECK: spender的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint_state.mint.as_ref(), spender.key().as_ref()],
+        bump,
+        constraint = spender_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub spender_blacklist: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"access_registry", mint_state.mint.as_ref()],
+        bump,
+    )]
+    pub access_registry: Box<Account<'info, AccessRegistryState>>,
+
+    /// 中继者的操作员记录，非操作员或owner自行提交时传入None
+    #[account(
+        seeds = [b"operator", mint_state.mint.as_ref(), relayer.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
 
     pub token_program: Program<'info, Token2022>,
     pub system_program: Program<'info, System>,
-    pub clock: Sysvar<'info, Clock>
+    pub clock: Sysvar<'info, Clock>,
+
+    /// CHECK: 指令sysvar，地址由约束校验
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: AccountInfo<'info>,
 } 
 
+#[derive(Accounts)]
+pub struct InvalidateNonces<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: 仅用于派生许可状态PDA
+    pub spender: AccountInfo<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, anchor_spl::token_interface::Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = AllowanceState::SIZE,
+        seeds = [b"allowance", token_mint.key().as_ref(), owner.key().as_ref(), spender.key().as_ref()],
+        bump
+    )]
+    pub allowance: Account<'info, AllowanceState>,
+
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct PermitParams {
     pub amount: u64,
@@ -116,6 +246,38 @@ pub struct PermitMessage {
     pub version: [u8; 32]
 } 
 
+impl PermitMessage {
+    /// 根据许可参数重建owner需要签名的消息
+    pub fn new(
+        program_id: &Pubkey,
+        mint: &Pubkey,
+        owner: Pubkey,
+        spender: Pubkey,
+        params: &PermitParams,
+    ) -> Self {
+        let mut version = [0u8; 32];
+        version[..PERMIT_VERSION.len()].copy_from_slice(PERMIT_VERSION);
+
+        Self {
+            contract: *program_id,
+            domain_separator: Self::domain_separator(program_id, mint),
+            owner,
+            spender,
+            amount: params.amount,
+            nonce: params.nonce.unwrap_or_default(),
+            deadline: params.deadline,
+            scope: params.scope,
+            chain_id: PERMIT_CHAIN_ID,
+            version,
+        }
+    }
+
+    /// 域分隔符，将签名绑定到本程序和具体的mint上
+    pub fn domain_separator(program_id: &Pubkey, mint: &Pubkey) -> [u8; 32] {
+        hashv(&[PERMIT_DOMAIN, program_id.as_ref(), mint.as_ref(), PERMIT_VERSION]).to_bytes()
+    }
+}
+
 /// 许可授权事件，记录EIP-2612兼容的许可授权信息
 #[event]
 pub struct PermitGranted {
@@ -129,12 +291,13 @@ pub struct PermitGranted {
     pub scope: PermitScope,
 }    
 
-impl PermitScope {
-    pub const TRANSFER: PermitScope = PermitScope {
-        one_time: false,
-        permanent: true,
-        transfer: true,
-        burn: false,
-        all: false
-    };
-}
\ No newline at end of file
+/// 随机数作废事件，记录owner主动取消的许可
+#[event]
+pub struct NoncesInvalidated {
+    /// 代币所有者地址
+    pub owner: Pubkey,
+    /// 被授权者地址
+    pub spender: Pubkey,
+    /// 新的随机数，小于该值的许可签名均已失效
+    pub nonce: u64,
+}
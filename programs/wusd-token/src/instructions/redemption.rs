@@ -0,0 +1,344 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::{self, burn as token_burn, Token2022};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::access::{Permission, Role};
+use crate::error::WusdError;
+use crate::state::{
+    AccessRegistryState, AuthorityState, OperatorRecord, PauseState, RedemptionRequest,
+    RedemptionStatus, RoleState,
+};
+use crate::utils::{calculate_transfer_fee, require_has_access, transfer_checked_with_hook};
+
+/// 发起赎回请求，将代币托管到程序控制的赎回金库
+/// * `ctx` - 上下文
+/// * `request_id` - 请求编号，同一owner下不可重复
+/// * `amount` - 赎回数量
+/// * `bank_ref_hash` - 银行收款信息的哈希
+pub fn request_redemption<'info>(
+    ctx: Context<'_, '_, '_, 'info, RequestRedemption<'info>>,
+    request_id: u64,
+    amount: u64,
+    bank_ref_hash: [u8; 32],
+) -> Result<()> {
+    // 检查访问权限
+    require_has_access(
+        ctx.accounts.owner.key(),
+        Permission::CAN_BURN,
+        Some(amount),
+        &ctx.accounts.pause_state,
+        Some(&ctx.accounts.access_registry),
+        ctx.accounts.operator_record.as_deref(),
+    )?;
+
+    // 验证余额充足
+    require!(
+        ctx.accounts.token_account.amount >= amount,
+        WusdError::InsufficientBalance
+    );
+
+    // 将代币转入赎回金库，托管数量以扣除转账手续费后的到账金额为准
+    let fee = calculate_transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+    let escrowed = amount.checked_sub(fee).ok_or(WusdError::InvalidAmount)?;
+    transfer_checked_with_hook(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::TransferChecked {
+                from: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: ctx.accounts.redemption_vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.redemption_request.set_inner(RedemptionRequest {
+        mint: ctx.accounts.token_mint.key(),
+        owner: ctx.accounts.owner.key(),
+        token_account: ctx.accounts.token_account.key(),
+        request_id,
+        amount: escrowed,
+        bank_ref_hash,
+        status: RedemptionStatus::Pending,
+        settlement_id: None,
+        requested_at: now,
+        resolved_at: 0,
+        bump: *ctx.bumps.get("redemption_request").unwrap(),
+    });
+
+    emit!(RedemptionRequested {
+        request: ctx.accounts.redemption_request.key(),
+        owner: ctx.accounts.owner.key(),
+        amount: escrowed,
+        bank_ref_hash,
+        status: RedemptionStatus::Pending,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// 完成赎回：销毁托管的代币并记录结算编号
+/// * `ctx` - 上下文
+/// * `settlement_id` - 法币兑付的结算编号
+pub fn fulfill_redemption(ctx: Context<ResolveRedemption>, settlement_id: String) -> Result<()> {
+    require!(
+        ctx.accounts.burner_role.has_role(ctx.accounts.operator.key()),
+        WusdError::MissingRole
+    );
+    require!(
+        settlement_id.len() <= RedemptionRequest::MAX_SETTLEMENT_ID_LEN,
+        WusdError::ReferenceTooLong
+    );
+    ctx.accounts.redemption_request.validate_pending()?;
+
+    let amount = ctx.accounts.redemption_request.amount;
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    token_burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::Burn {
+                mint: ctx.accounts.token_mint.to_account_info(),
+                from: ctx.accounts.redemption_vault.to_account_info(),
+                authority: ctx.accounts.authority_state.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.redemption_request;
+    request.status = RedemptionStatus::Fulfilled;
+    request.settlement_id = Some(settlement_id.clone());
+    request.resolved_at = now;
+
+    emit!(RedemptionFulfilled {
+        request: request.key(),
+        operator: ctx.accounts.operator.key(),
+        amount,
+        settlement_id,
+        status: RedemptionStatus::Fulfilled,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+/// 拒绝赎回：将托管的代币退回owner
+///
+/// 退款由authority_state转出，转账钩子对其豁免，因此在此校验owner未被列入黑名单、
+/// 退款账户未被冻结。退款同样按当前费率收取转账手续费，owner实际到账为托管数量扣除该手续费。
+/// * `ctx` - 上下文
+pub fn reject_redemption<'info>(ctx: Context<'_, '_, '_, 'info, ResolveRedemption<'info>>) -> Result<()> {
+    require!(
+        ctx.accounts.burner_role.has_role(ctx.accounts.operator.key()),
+        WusdError::MissingRole
+    );
+    ctx.accounts.redemption_request.validate_pending()?;
+
+    let refund_account = ctx.accounts.refund_account.as_ref()
+        .ok_or(error!(WusdError::InvalidOwner))?;
+    require!(
+        refund_account.key() == ctx.accounts.redemption_request.token_account,
+        WusdError::InvalidOwner
+    );
+    require!(
+        ctx.accounts.owner_blacklist.data_is_empty(),
+        WusdError::Blacklisted
+    );
+    require!(!refund_account.is_frozen(), WusdError::AccountFrozen);
+
+    let amount = ctx.accounts.redemption_request.amount;
+    let fee = calculate_transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    transfer_checked_with_hook(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_2022::TransferChecked {
+                from: ctx.accounts.redemption_vault.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+                to: refund_account.to_account_info(),
+                authority: ctx.accounts.authority_state.to_account_info(),
+            },
+            &[seeds],
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let request = &mut ctx.accounts.redemption_request;
+    request.status = RedemptionStatus::Rejected;
+    request.resolved_at = now;
+
+    emit!(RedemptionRejected {
+        request: request.key(),
+        operator: ctx.accounts.operator.key(),
+        amount,
+        fee,
+        status: RedemptionStatus::Rejected,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct RequestRedemption<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = token_account.owner == owner.key() @ WusdError::InvalidOwner,
+        constraint = token_account.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    /// 赎回金库，authority_state PDA的关联代币账户
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority_state
+    )]
+    pub redemption_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = RedemptionRequest::SIZE,
+        seeds = [
+            b"redemption",
+            token_mint.key().as_ref(),
+            owner.key().as_ref(),
+            &request_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub redemption_request: Box<Account<'info, RedemptionRequest>>,
+
+    #[account(
+        seeds = [b"pause_state", token_mint.key().as_ref()],
+        bump
+    )]
+    pub pause_state: Account<'info, PauseState>,
+    #[account(
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
+    pub access_registry: Account<'info, AccessRegistryState>,
+    /// CHECK: owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = owner_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub owner_blacklist: AccountInfo<'info>,
+    /// owner的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveRedemption<'info> {
+    pub operator: Signer<'info>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Burner.seed()],
+        bump = burner_role.bump
+    )]
+    pub burner_role: Account<'info, RoleState>,
+
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority_state
+    )]
+    pub redemption_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = redemption_request.mint == token_mint.key() @ WusdError::InvalidMint
+    )]
+    pub redemption_request: Box<Account<'info, RedemptionRequest>>,
+
+    /// CHECK: 赎回owner的黑名单条目PDA，账户不存在表示未被列入黑名单，仅拒绝赎回时校验
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), redemption_request.owner.as_ref()],
+        bump
+    )]
+    pub owner_blacklist: AccountInfo<'info>,
+
+    /// 退款账户，仅拒绝赎回时需要
+    #[account(mut)]
+    pub refund_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// 赎回请求事件
+#[event]
+pub struct RedemptionRequested {
+    pub request: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub bank_ref_hash: [u8; 32],
+    pub status: RedemptionStatus,
+    pub timestamp: i64,
+}
+
+/// 赎回完成事件
+#[event]
+pub struct RedemptionFulfilled {
+    pub request: Pubkey,
+    pub operator: Pubkey,
+    pub amount: u64,
+    pub settlement_id: String,
+    pub status: RedemptionStatus,
+    pub timestamp: i64,
+}
+
+/// 赎回拒绝事件
+#[event]
+pub struct RedemptionRejected {
+    pub request: Pubkey,
+    pub operator: Pubkey,
+    pub amount: u64,
+    /// 退款时扣除的转账手续费
+    pub fee: u64,
+    pub status: RedemptionStatus,
+    pub timestamp: i64,
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{AuthorityState, RoleState};
+
+/// 救援误转入authority_state PDA名下代币账户的其他代币
+///
+/// WUSD本身（如赎回托管金库中的代币）不可救援
+/// * `ctx` - 上下文
+/// * `amount` - 救援数量
+pub fn rescue_tokens(ctx: Context<RescueTokens>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.rescuer_role.has_role(ctx.accounts.authority.key()),
+        WusdError::MissingRole
+    );
+    require!(amount > 0, WusdError::InvalidAmount);
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let bump = *ctx.bumps.get("authority_state").unwrap();
+    let seeds: &[&[u8]] = &[b"authority", mint_key.as_ref(), &[bump]];
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.source.to_account_info(),
+                mint: ctx.accounts.rescue_mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.authority_state.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+        ctx.accounts.rescue_mint.decimals,
+    )?;
+
+    emit!(TokensRescued {
+        mint: mint_key,
+        rescued_mint: ctx.accounts.rescue_mint.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        authority: ctx.accounts.authority.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RescueTokens<'info> {
+    pub authority: Signer<'info>,
+
+    /// WUSD铸币账户
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Box<Account<'info, AuthorityState>>,
+
+    #[account(
+        seeds = [b"role", token_mint.key().as_ref(), &Role::Rescuer.seed()],
+        bump = rescuer_role.bump
+    )]
+    pub rescuer_role: Account<'info, RoleState>,
+
+    /// 被救援代币的铸币账户，不能是WUSD
+    #[account(constraint = rescue_mint.key() != token_mint.key() @ WusdError::InvalidMint)]
+    pub rescue_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// authority_state名下持有被救援代币的账户
+    #[account(
+        mut,
+        constraint = source.owner == authority_state.key() @ WusdError::InvalidOwner,
+        constraint = source.mint == rescue_mint.key() @ WusdError::InvalidMint
+    )]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// 接收被救援代币的账户
+    #[account(
+        mut,
+        constraint = destination.mint == rescue_mint.key() @ WusdError::InvalidMint
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// 代币救援事件
+#[event]
+pub struct TokensRescued {
+    pub mint: Pubkey,
+    pub rescued_mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use crate::access::Role;
+use crate::error::WusdError;
+use crate::state::{AuthorityState, RoleState};
+
+/// 授予角色
+///
+/// 管理员可以授予任意角色，其他账户需要拥有该角色的管理角色
+/// * `ctx` - 上下文
+/// * `role` - 角色
+/// * `member` - 被授予角色的地址
+pub fn grant_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+    ctx.accounts.load_role_state(role, *ctx.bumps.get("role_state").unwrap());
+    ctx.accounts.validate_role_admin()?;
+
+    if ctx.accounts.role_state.grant(member)? {
+        emit!(RoleGranted {
+            mint: ctx.accounts.token_mint.key(),
+            role,
+            member,
+            sender: ctx.accounts.authority.key(),
+        });
+    }
+
+    Ok(())
+}
+
+/// 撤销角色
+/// * `ctx` - 上下文
+/// * `role` - 角色
+/// * `member` - 被撤销角色的地址
+pub fn revoke_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+    ctx.accounts.load_role_state(role, *ctx.bumps.get("role_state").unwrap());
+    ctx.accounts.validate_role_admin()?;
+
+    ctx.accounts.role_state.revoke(member)?;
+
+    emit!(RoleRevoked {
+        mint: ctx.accounts.token_mint.key(),
+        role,
+        member,
+        sender: ctx.accounts.authority.key(),
+    });
+
+    Ok(())
+}
+
+/// 放弃自己持有的角色
+/// * `ctx` - 上下文
+/// * `role` - 角色
+pub fn renounce_role(ctx: Context<RenounceRole>, role: Role) -> Result<()> {
+    ctx.accounts.role_state.revoke(ctx.accounts.member.key())?;
+
+    emit!(RoleRevoked {
+        mint: ctx.accounts.role_state.mint,
+        role,
+        member: ctx.accounts.member.key(),
+        sender: ctx.accounts.member.key(),
+    });
+
+    Ok(())
+}
+
+/// 设置角色的管理角色，仅管理员可调用
+/// * `ctx` - 上下文
+/// * `role` - 角色
+/// * `admin_role` - 新的管理角色，None表示仅管理员可以管理
+pub fn set_role_admin(ctx: Context<ManageRole>, role: Role, admin_role: Option<Role>) -> Result<()> {
+    require!(
+        ctx.accounts.authority_state.is_admin(ctx.accounts.authority.key()),
+        WusdError::Unauthorized
+    );
+    ctx.accounts.load_role_state(role, *ctx.bumps.get("role_state").unwrap());
+
+    let previous_admin_role = ctx.accounts.role_state.admin_role;
+    ctx.accounts.role_state.admin_role = admin_role;
+
+    emit!(RoleAdminChanged {
+        mint: ctx.accounts.token_mint.key(),
+        role,
+        previous_admin_role,
+        new_admin_role: admin_role,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role)]
+pub struct ManageRole<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RoleState::SIZE,
+        seeds = [b"role", token_mint.key().as_ref(), &role.seed()],
+        bump
+    )]
+    pub role_state: Account<'info, RoleState>,
+
+    /// 调用者所持有的管理角色账户，管理员调用时可省略
+    pub admin_role_state: Option<Account<'info, RoleState>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ManageRole<'info> {
+    /// 首次使用时初始化角色账户
+    fn load_role_state(&mut self, role: Role, bump: u8) {
+        if self.role_state.is_uninitialized() {
+            self.role_state.mint = self.token_mint.key();
+            self.role_state.role = role;
+            self.role_state.bump = bump;
+        }
+    }
+
+    /// 验证调用者是管理员，或持有该角色的管理角色
+    fn validate_role_admin(&self) -> Result<()> {
+        let authority = self.authority.key();
+        if self.authority_state.is_admin(authority) {
+            return Ok(());
+        }
+
+        let admin_role = self.role_state.admin_role.ok_or(error!(WusdError::Unauthorized))?;
+        let admin_role_state = self.admin_role_state.as_ref().ok_or(error!(WusdError::Unauthorized))?;
+        require!(
+            admin_role_state.mint == self.token_mint.key()
+                && admin_role_state.role == admin_role
+                && admin_role_state.has_role(authority),
+            WusdError::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(role: Role)]
+pub struct RenounceRole<'info> {
+    pub member: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"role", role_state.mint.as_ref(), &role.seed()],
+        bump = role_state.bump
+    )]
+    pub role_state: Account<'info, RoleState>,
+}
+
+/// 角色授予事件
+#[event]
+pub struct RoleGranted {
+    /// 代币铸币账户
+    pub mint: Pubkey,
+    /// 角色
+    pub role: Role,
+    /// 被授予角色的地址
+    pub member: Pubkey,
+    /// 执行授予的地址
+    pub sender: Pubkey,
+}
+
+/// 角色撤销事件
+#[event]
+pub struct RoleRevoked {
+    /// 代币铸币账户
+    pub mint: Pubkey,
+    /// 角色
+    pub role: Role,
+    /// 被撤销角色的地址
+    pub member: Pubkey,
+    /// 执行撤销的地址
+    pub sender: Pubkey,
+}
+
+/// 管理角色变更事件
+#[event]
+pub struct RoleAdminChanged {
+    /// 代币铸币账户
+    pub mint: Pubkey,
+    /// 角色
+    pub role: Role,
+    /// 原管理角色
+    pub previous_admin_role: Option<Role>,
+    /// 新管理角色
+    pub new_admin_role: Option<Role>,
+}
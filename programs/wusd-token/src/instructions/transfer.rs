@@ -1,14 +1,25 @@
+use crate::access::Permission;
 use crate::error::WusdError;
-use crate::state::{AccessRegistryState, FreezeState, MintState, PauseState, PermitState};
-use crate::utils::require_has_access;
+use crate::state::{AccessRegistryState, FreezeState, MintState, OperatorRecord, PauseState, AllowanceState};
+use crate::utils::{
+    calculate_transfer_fee, log_memo, require_has_access, require_kyc_balance, require_kyc_outflow,
+    transfer_checked_with_hook,
+};
+use crate::instructions::allowance::ApprovalEvent;
 use anchor_lang::prelude::*;
-use anchor_spl::token_2022::{self, transfer_checked, Token2022};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::{self, Token2022};
 use anchor_spl::token_interface::TokenAccount;
 
 /// 转账WUSD代币
 /// * `ctx` - 转账上下文
 /// * `amount` - 转账数量
-pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
+/// * `memo` - 转账备注（可选），如发票编号
+pub fn transfer<'info>(
+    ctx: Context<'_, '_, '_, 'info, Transfer<'info>>,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<()> {
     // 验证系统未被暂停
     ctx.accounts.pause_state.validate_not_paused()?;
     require!(amount > 0, WusdError::InvalidAmount);
@@ -22,14 +33,43 @@ pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
     // 检查访问权限
     require_has_access(
         ctx.accounts.from.key(),
-        true,
+        Permission::CAN_TRANSFER,
         Some(amount),
         &ctx.accounts.pause_state,
         Some(&ctx.accounts.access_registry),
+        ctx.accounts.operator_record.as_deref(),
     )?;
 
-    // 执行转账
-    transfer_checked(
+    // 记录备注
+    if let Some(memo) = memo.as_deref() {
+        let memo_program = ctx.accounts.memo_program.as_ref().ok_or(WusdError::MemoProgramMissing)?;
+        log_memo(memo_program, &ctx.accounts.from.to_account_info(), memo)?;
+    }
+
+    // 执行转账，手续费由Token-2022从接收方到账金额中扣留
+    let fee = calculate_transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+
+    // 检查双方KYC等级限额
+    let mint_key = ctx.accounts.token_mint.key();
+    require_kyc_outflow(
+        &mint_key,
+        &ctx.accounts.from.key(),
+        &ctx.accounts.from_kyc,
+        &ctx.accounts.from_kyc_limits,
+        &ctx.accounts.from_kyc_outflow,
+        *ctx.bumps.get("from_kyc_outflow").unwrap(),
+        &ctx.accounts.from.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+    require_kyc_balance(
+        &mint_key,
+        &ctx.accounts.to_kyc,
+        &ctx.accounts.to_kyc_limits,
+        ctx.accounts.to_token.amount.saturating_add(amount - fee),
+    )?;
+
+    transfer_checked_with_hook(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             token_2022::TransferChecked {
@@ -38,9 +78,9 @@ pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
                 to: ctx.accounts.to_token.to_account_info(),
                 authority: ctx.accounts.from.to_account_info(),
             },
-        ),
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount,
-        6, // 使用固定的小数位数
+        ctx.accounts.token_mint.decimals,
     )?;
 
     // 发送转账事件
@@ -48,26 +88,35 @@ pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
     emit!(TransferEvent {
         from: ctx.accounts.from.key(),
         to: ctx.accounts.to.key(),
-        amount: amount,
-        fee: 0,
+        amount,
+        fee,
         timestamp: clock.unix_timestamp,
-        memo: None,
+        memo,
     });
 
     Ok(())
 }
 
-pub fn transfer_from(ctx: Context<TransferFrom>, amount: u64) -> Result<()> {
+/// 使用授权额度转账WUSD代币
+/// * `ctx` - 转账上下文
+/// * `amount` - 转账数量
+/// * `memo` - 转账备注（可选），由spender签名
+pub fn transfer_from<'info>(
+    ctx: Context<'_, '_, '_, 'info, TransferFrom<'info>>,
+    amount: u64,
+    memo: Option<String>,
+) -> Result<()> {
     // 验证授权有效性
     let current_time = Clock::get()?.unix_timestamp;
     require!(
-        ctx.accounts.permit.expiration > current_time,
+        ctx.accounts.allowance.expiration > current_time,
         WusdError::ExpiredPermit
     );
     require!(
-        ctx.accounts.permit.amount >= amount,
+        ctx.accounts.allowance.amount >= amount,
         WusdError::InsufficientAllowance
     );
+    ctx.accounts.allowance.validate_transfer_scope()?;
 
     // 验证 token account 所有权
     require!(
@@ -78,10 +127,11 @@ pub fn transfer_from(ctx: Context<TransferFrom>, amount: u64) -> Result<()> {
     // 检查访问权限
     require_has_access(
         ctx.accounts.spender.key(),
-        true,
+        Permission::CAN_TRANSFER_FROM,
         Some(amount),
         &ctx.accounts.pause_state,
         Some(&ctx.accounts.access_registry),
+        ctx.accounts.operator_record.as_deref(),
     )?;
 
     // 检查冻结状态
@@ -91,29 +141,67 @@ pub fn transfer_from(ctx: Context<TransferFrom>, amount: u64) -> Result<()> {
     );
     require!(!ctx.accounts.to_token.is_frozen(), WusdError::AccountFrozen);
 
-    // 直接使用spender作为authority执行转账
-    // 不再尝试使用PDA签名，而是使用标准的Token2022 approve/transfer机制
-    transfer_checked(
-        CpiContext::new(
+    // 使用程序控制的委托PDA签名转账，额度以程序内的授权账户为准
+    let mint_key = ctx.accounts.token_mint.key();
+    let delegate_bump = *ctx.bumps.get("delegate").unwrap();
+    let seeds: &[&[u8]] = &[b"delegate", mint_key.as_ref(), &[delegate_bump]];
+    if let Some(memo) = memo.as_deref() {
+        let memo_program = ctx.accounts.memo_program.as_ref().ok_or(WusdError::MemoProgramMissing)?;
+        log_memo(memo_program, &ctx.accounts.spender.to_account_info(), memo)?;
+    }
+    let fee = calculate_transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+
+    // 检查owner及接收者的KYC等级限额
+    require_kyc_outflow(
+        &mint_key,
+        &ctx.accounts.owner.key(),
+        &ctx.accounts.owner_kyc,
+        &ctx.accounts.owner_kyc_limits,
+        &ctx.accounts.owner_kyc_outflow,
+        *ctx.bumps.get("owner_kyc_outflow").unwrap(),
+        &ctx.accounts.spender.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+    require_kyc_balance(
+        &mint_key,
+        &ctx.accounts.recipient_kyc,
+        &ctx.accounts.recipient_kyc_limits,
+        ctx.accounts.to_token.amount.saturating_add(amount - fee),
+    )?;
+
+    transfer_checked_with_hook(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             token_2022::TransferChecked {
                 from: ctx.accounts.from_token.to_account_info(),
                 mint: ctx.accounts.token_mint.to_account_info(),
                 to: ctx.accounts.to_token.to_account_info(),
-                authority: ctx.accounts.spender.to_account_info(), // 使用spender作为authority
-            }
-        ),
+                authority: ctx.accounts.delegate.to_account_info(),
+            },
+            &[seeds],
+        ).with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount,
-        6, // 使用固定的小数位数
+        ctx.accounts.token_mint.decimals,
     )?;
 
-    // 更新授权额度
-    ctx.accounts.permit.amount = ctx
-        .accounts
-        .permit
-        .amount
-        .checked_sub(amount)
-        .ok_or(WusdError::InsufficientAllowance)?;
+    emit!(TransferEvent {
+        from: ctx.accounts.owner.key(),
+        to: ctx.accounts.to_token.owner,
+        amount,
+        fee,
+        timestamp: current_time,
+        memo,
+    });
+
+    // 更新授权额度，单次许可使用后失效
+    ctx.accounts.allowance.consume(amount)?;
+    emit!(ApprovalEvent {
+        mint: mint_key,
+        owner: ctx.accounts.owner.key(),
+        spender: ctx.accounts.spender.key(),
+        amount: ctx.accounts.allowance.amount,
+    });
     Ok(())
 }
 
@@ -126,29 +214,46 @@ pub struct TransferFrom<'info> {
     pub owner: AccountInfo<'info>,
     #[account(
         mut,
-        constraint = from_token.owner == owner.key()
+        constraint = from_token.owner == owner.key(),
+        constraint = from_token.delegate == COption::Some(delegate.key()) @ WusdError::DelegateNotApproved
     )]
     pub from_token: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
     #[account(mut)]
     pub to_token: Box<InterfaceAccount<'info, anchor_spl::token_interface::TokenAccount>>,
     #[account(
-        seeds = [
-            b"permit",
-            owner.key().as_ref(),
-            spender.key().as_ref()
-        ],
-        bump = permit.bump,
+        mut,
+        seeds = [b"allowance", token_mint.key().as_ref(), owner.key().as_ref(), spender.key().as_ref()],
+        bump = allowance.bump,
         has_one = owner,
         has_one = spender,
     )]
-    pub permit: Account<'info, PermitState>,
-    #[account(mut)]
+    pub allowance: Account<'info, AllowanceState>,
+    #[account(
+        seeds = [b"mint_state", token_mint.key().as_ref()],
+        bump,
+        constraint = mint_state.mint == token_mint.key() @ WusdError::InvalidMint,
+        constraint = mint_state.decimals == token_mint.decimals @ WusdError::InvalidDecimals
+    )]
     pub mint_state: Box<Account<'info, MintState>>,
+    #[account(
+        seeds = [b"pause_state", token_mint.key().as_ref()],
+        bump,
+    )]
     pub pause_state: Account<'info, PauseState>,
+    #[account(
+        seeds = [b"access_registry", token_mint.key().as_ref()],
+        bump,
+    )]
     pub access_registry: Account<'info, AccessRegistryState>,
     pub token_program: Program<'info, Token2022>,
     #[account(mut)]
     pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    /// CHECK: 程序控制的委托PDA，owner需预先通过`approve_delegate`授权
+    #[account(
+        seeds = [b"delegate", token_mint.key().as_ref()],
+        bump
+    )]
+    pub delegate: AccountInfo<'info>,
     #[account(
         seeds = [b"freeze", from_token.key().as_ref()],
         bump,
@@ -161,6 +266,67 @@ pub struct TransferFrom<'info> {
         constraint = !to_freeze_state.is_frozen @ WusdError::AccountFrozen
     )]
     pub to_freeze_state: Account<'info, FreezeState>,
+    /// CHECK: owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump,
+        constraint = owner_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub owner_blacklist: AccountInfo<'info>,
+    /// CHECK: 接收者的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), to_token.owner.as_ref()],
+        bump,
+        constraint = recipient_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub recipient_blacklist: AccountInfo<'info>,
+    /// CHECK: 接收者的白名单条目PDA，仅白名单模式下要求账户存在
+    #[account(
+        seeds = [b"allowlist", token_mint.key().as_ref(), to_token.owner.as_ref()],
+        bump,
+        constraint = !access_registry.allowlist_enabled || !recipient_allowlist.data_is_empty()
+            @ WusdError::RecipientNotAllowlisted
+    )]
+    pub recipient_allowlist: AccountInfo<'info>,
+    /// CHECK: spender的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), spender.key().as_ref()],
+        bump,
+        constraint = spender_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub spender_blacklist: AccountInfo<'info>,
+    /// CHECK: owner的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub owner_kyc: AccountInfo<'info>,
+    /// CHECK: owner所在KYC等级的限额PDA，在`require_kyc_outflow`中校验地址
+    pub owner_kyc_limits: AccountInfo<'info>,
+    /// CHECK: owner的每日流出累计PDA，需要时由spender付费创建
+    #[account(
+        mut,
+        seeds = [b"kyc_outflow", token_mint.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub owner_kyc_outflow: AccountInfo<'info>,
+    /// CHECK: 接收者的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", token_mint.key().as_ref(), to_token.owner.as_ref()],
+        bump
+    )]
+    pub recipient_kyc: AccountInfo<'info>,
+    /// CHECK: 接收者所在KYC等级的限额PDA，在`require_kyc_balance`中校验地址
+    pub recipient_kyc_limits: AccountInfo<'info>,
+    /// spender的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", token_mint.key().as_ref(), spender.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
+    /// CHECK: SPL Memo程序，仅附带备注时需要
+    #[account(address = spl_memo::id())]
+    pub memo_program: Option<AccountInfo<'info>>,
     pub system_program: Program<'info, System>,
 }
 
@@ -185,13 +351,20 @@ pub struct Transfer<'info> {
     pub token_program: Program<'info, Token2022>,
     #[account(mut)]
     pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+    #[account(
+        seeds = [b"mint_state", token_mint.key().as_ref()],
+        bump,
+        constraint = mint_state.mint == token_mint.key() @ WusdError::InvalidMint,
+        constraint = mint_state.decimals == token_mint.decimals @ WusdError::InvalidDecimals
+    )]
+    pub mint_state: Box<Account<'info, MintState>>,
     #[account(
         seeds = [b"pause_state", from_token.mint.as_ref()],
         bump,
     )]
     pub pause_state: Account<'info, PauseState>,
     #[account(
-        seeds = [b"access_registry"],
+        seeds = [b"access_registry", token_mint.key().as_ref()],
         bump,
     )]
     pub access_registry: Account<'info, AccessRegistryState>,
@@ -207,6 +380,61 @@ pub struct Transfer<'info> {
         constraint = !to_freeze_state.is_frozen @ WusdError::AccountFrozen
     )]
     pub to_freeze_state: Account<'info, FreezeState>,
+    /// CHECK: 发送者的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), from.key().as_ref()],
+        bump,
+        constraint = from_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub from_blacklist: AccountInfo<'info>,
+    /// CHECK: 接收者的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", token_mint.key().as_ref(), to.key().as_ref()],
+        bump,
+        constraint = to_blacklist.data_is_empty() @ WusdError::Blacklisted
+    )]
+    pub to_blacklist: AccountInfo<'info>,
+    /// CHECK: 接收者的白名单条目PDA，仅白名单模式下要求账户存在
+    #[account(
+        seeds = [b"allowlist", token_mint.key().as_ref(), to.key().as_ref()],
+        bump,
+        constraint = !access_registry.allowlist_enabled || !recipient_allowlist.data_is_empty()
+            @ WusdError::RecipientNotAllowlisted
+    )]
+    pub recipient_allowlist: AccountInfo<'info>,
+    /// CHECK: 发送者的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", token_mint.key().as_ref(), from.key().as_ref()],
+        bump
+    )]
+    pub from_kyc: AccountInfo<'info>,
+    /// CHECK: 发送者所在KYC等级的限额PDA，在`require_kyc_outflow`中校验地址
+    pub from_kyc_limits: AccountInfo<'info>,
+    /// CHECK: 发送者的每日流出累计PDA，需要时由发送者付费创建
+    #[account(
+        mut,
+        seeds = [b"kyc_outflow", token_mint.key().as_ref(), from.key().as_ref()],
+        bump
+    )]
+    pub from_kyc_outflow: AccountInfo<'info>,
+    /// CHECK: 接收者的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", token_mint.key().as_ref(), to.key().as_ref()],
+        bump
+    )]
+    pub to_kyc: AccountInfo<'info>,
+    /// CHECK: 接收者所在KYC等级的限额PDA，在`require_kyc_balance`中校验地址
+    pub to_kyc_limits: AccountInfo<'info>,
+    /// 发送者的操作员记录，非操作员时传入None
+    #[account(
+        seeds = [b"operator", token_mint.key().as_ref(), from.key().as_ref()],
+        bump = operator_record.bump
+    )]
+    pub operator_record: Option<Account<'info, OperatorRecord>>,
+    /// CHECK: SPL Memo程序，仅附带备注时需要
+    #[account(address = spl_memo::id())]
+    pub memo_program: Option<AccountInfo<'info>>,
+    pub system_program: Program<'info, System>,
 }
 
 #[event]
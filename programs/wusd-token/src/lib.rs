@@ -1,4 +1,5 @@
 //! WUSD Token 程序 
+#![allow(clippy::result_large_err)]
 use anchor_lang::prelude::*;
 use anchor_spl::token_2022; 
 use anchor_spl::token_interface::Mint;
@@ -6,20 +7,34 @@ use anchor_spl::token_2022::ID as TOKEN_PROGRAM_ID;
 use spl_token_2022::instruction::AuthorityType;   
 
 mod instructions; 
-mod error;
-mod state; 
+pub mod error;
+pub mod state;
 mod utils;
 mod access;
 
+use access::Role;
+use error::WusdError;
 use state::{AuthorityState, MintState, PauseState, AccessRegistryState};
 
 use instructions::mint::*; 
 use instructions::burn::*;
 use instructions::transfer::*;
 use instructions::permit::*;
+use instructions::allowance::*;
 use instructions::operator::*;
 use instructions::pause::*;
 use instructions::freeze::*; 
+use instructions::role::*;
+use instructions::admin::*;
+use instructions::minter::*;
+use instructions::redemption::*;
+use instructions::blacklist::*;
+use instructions::metadata::*;
+use instructions::fee::*;
+use instructions::memo::*;
+use instructions::allowlist::*;
+use instructions::kyc::*;
+use instructions::rescue::*;
 
 declare_id!("8nBbkdsTkqbrnrbVTUxyciQNvT6Q5B3pZkPQmP3nnuwU");
 
@@ -29,49 +44,69 @@ pub mod wusd_token {
     pub fn initialize_access_registry(ctx: Context<InitializeAccessRegistry>) -> Result<()> {
         let access_registry = &mut ctx.accounts.access_registry;
         access_registry.authority = ctx.accounts.authority.key();
+        access_registry.mint = ctx.accounts.token_mint.key();
         access_registry.operator_count = 0;
-        access_registry.operators = [Pubkey::default(); 10];
+        access_registry.allowlist_enabled = false;
         access_registry.initialized = true;
         Ok(())
     }
 
-    pub fn initialize(ctx: Context<Initialize>, decimals: u8) -> Result<()> {
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        decimals: u8,
+        name: String,
+        symbol: String,
+        uri: String,
+        transfer_fee_basis_points: u16,
+        maximum_fee: u64,
+    ) -> Result<()> {
         msg!("Starting initialization...");
         msg!("Authority: {}", ctx.accounts.authority.key());
         msg!("Mint: {}", ctx.accounts.token_mint.key());
 
         // 1. 初始化状态账户
         let authority_state = &mut ctx.accounts.authority_state;
-        authority_state.admin = ctx.accounts.authority.key();
-        authority_state.minter = ctx.accounts.authority.key();
-        authority_state.pauser = ctx.accounts.authority.key();
+        authority_state.set_inner(AuthorityState::initialize(ctx.accounts.authority.key()));
 
         let mint_state = &mut ctx.accounts.mint_state;
         mint_state.mint = ctx.accounts.token_mint.key();
         mint_state.decimals = decimals;
+        mint_state.max_supply = u64::MAX;
 
         let pause_state = &mut ctx.accounts.pause_state;
         pause_state.paused = false;
 
-        // 2. 转移mint的authority给authority_state PDA
-        let mint_key = ctx.accounts.token_mint.key();
-        let seeds = &[b"authority", mint_key.as_ref()]; 
-        let (_authority_pda, bump) = Pubkey::find_program_address(seeds, ctx.program_id);
-        let _bump_bytes = &[bump];
+        // 2. 创建mint账户，铸币、冻结权限、永久委托和元数据更新权限均为authority_state PDA
+        utils::create_mint_with_extensions(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.authority_state.key(),
+            decimals,
+            transfer_fee_basis_points,
+            maximum_fee,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
 
-        token_2022::set_authority(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                token_2022::SetAuthority {
-                    current_authority: ctx.accounts.authority.to_account_info(),
-                    account_or_mint: ctx.accounts.token_mint.to_account_info(),
-                }
-            ),
-            AuthorityType::MintTokens,
-            Some(ctx.accounts.authority_state.key()),
+        // 3. 写入代币元数据，并补足mint因元数据增长所需的租金
+        let mint_key = ctx.accounts.token_mint.key();
+        let bump = *ctx.bumps.get("authority_state").unwrap();
+        instructions::metadata::initialize_token_metadata(
+            &ctx.accounts.token_program.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.authority_state.to_account_info(),
+            &[b"authority", mint_key.as_ref(), &[bump]],
+            name,
+            symbol,
+            uri,
+        )?;
+        utils::top_up_rent(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.token_mint.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
         )?;
 
-        // 3. 发出初始化事件
+        // 4. 发出初始化事件
         emit!(InitializeEvent {
             authority: ctx.accounts.authority.key(),
             mint: ctx.accounts.token_mint.key(),
@@ -90,18 +125,17 @@ pub mod wusd_token {
 
         // 1. 初始化状态账户
         let authority_state = &mut ctx.accounts.authority_state;
-        authority_state.admin = ctx.accounts.authority.key();
-        authority_state.minter = ctx.accounts.authority.key();
-        authority_state.pauser = ctx.accounts.authority.key();
+        authority_state.set_inner(AuthorityState::initialize(ctx.accounts.authority.key()));
 
         let mint_state = &mut ctx.accounts.mint_state;
         mint_state.mint = ctx.accounts.token_mint.key();
         mint_state.decimals = decimals;
+        mint_state.max_supply = u64::MAX;
 
         let pause_state = &mut ctx.accounts.pause_state;
         pause_state.paused = false;
 
-        // 2. 转移mint的authority给authority_state PDA
+        // 2. 转移mint的铸币和冻结authority给authority_state PDA
         let mint_key = ctx.accounts.token_mint.key();
         let seeds = &[b"authority", mint_key.as_ref()]; 
         let (_authority_pda, bump) = Pubkey::find_program_address(seeds, ctx.program_id);
@@ -119,6 +153,18 @@ pub mod wusd_token {
             Some(ctx.accounts.authority_state.key()),
         )?;
 
+        token_2022::set_authority(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_2022::SetAuthority {
+                    current_authority: ctx.accounts.authority.to_account_info(),
+                    account_or_mint: ctx.accounts.token_mint.to_account_info(),
+                }
+            ),
+            AuthorityType::FreezeAccount,
+            Some(ctx.accounts.authority_state.key()),
+        )?;
+
         // 3. 发出初始化事件
         emit!(InitializeEvent {
             authority: ctx.accounts.authority.key(),
@@ -131,8 +177,28 @@ pub mod wusd_token {
     }
     
     /// 铸造WUSD代币 
-    pub fn mint(ctx: Context<MintAccounts>, amount: u64, bump: u8) -> Result<()> {
-        instructions::mint::mint(ctx, amount, bump) 
+    pub fn mint(ctx: Context<MintAccounts>, amount: u64, bump: u8, reference: Option<String>) -> Result<()> {
+        instructions::mint::mint(ctx, amount, bump, reference) 
+    }
+    
+    /// 设置最大供应量
+    pub fn set_max_supply(ctx: Context<UpdateMintConfig>, max_supply: u64) -> Result<()> {
+        instructions::mint::set_max_supply(ctx, max_supply)
+    }
+    
+    /// 设置全局铸币速率限制
+    pub fn set_mint_rate_limit(ctx: Context<UpdateMintConfig>, limit: u64, window: i64) -> Result<()> {
+        instructions::mint::set_mint_rate_limit(ctx, limit, window)
+    }
+
+    /// 设置单个铸币者的铸币速率限制
+    pub fn set_minter_rate_limit(
+        ctx: Context<SetMinterRateLimit>,
+        minter: Pubkey,
+        limit: u64,
+        window: i64,
+    ) -> Result<()> {
+        instructions::mint::set_minter_rate_limit(ctx, minter, limit, window)
     }
     
     /// 处理授权许可请求，允许代币持有者授权其他账户使用其代币
@@ -140,14 +206,52 @@ pub mod wusd_token {
         instructions::permit::permit(ctx, params) 
     }
 
+    /// 作废尚未提交的授权许可签名
+    pub fn invalidate_nonces(ctx: Context<InvalidateNonces>, new_nonce: u64) -> Result<()> {
+        instructions::permit::invalidate_nonces(ctx, new_nonce)
+    }
+
+    /// 将代币账户委托给程序控制的PDA
+    pub fn approve_delegate(ctx: Context<ApproveDelegate>) -> Result<()> {
+        instructions::allowance::approve_delegate(ctx)
+    }
+
+    /// 设置授权额度
+    pub fn approve(ctx: Context<ManageAllowance>, amount: u64) -> Result<()> {
+        instructions::allowance::approve(ctx, amount)
+    }
+
+    /// 增加授权额度
+    pub fn increase_allowance(ctx: Context<ManageAllowance>, added_value: u64) -> Result<()> {
+        instructions::allowance::increase_allowance(ctx, added_value)
+    }
+
+    /// 减少授权额度
+    pub fn decrease_allowance(ctx: Context<ManageAllowance>, subtracted_value: u64) -> Result<()> {
+        instructions::allowance::decrease_allowance(ctx, subtracted_value)
+    }
+
+    /// 撤销授权
+    pub fn revoke(ctx: Context<ManageAllowance>) -> Result<()> {
+        instructions::allowance::revoke(ctx)
+    }
+
     /// 转账WUSD代币 
-    pub fn transfer(ctx: Context<Transfer>, amount: u64) -> Result<()> {
-        instructions::transfer::transfer(ctx, amount) 
+    pub fn transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, Transfer<'info>>,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        instructions::transfer::transfer(ctx, amount, memo)
     } 
 
     /// 使用授权额度转账WUSD代币 
-    pub fn transfer_from(ctx: Context<TransferFrom>, amount: u64) -> Result<()> {
-        instructions::transfer::transfer_from(ctx, amount) 
+    pub fn transfer_from<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferFrom<'info>>,
+        amount: u64,
+        memo: Option<String>,
+    ) -> Result<()> {
+        instructions::transfer::transfer_from(ctx, amount, memo)
     } 
 
     /// 暂停合约
@@ -165,16 +269,208 @@ pub mod wusd_token {
         instructions::burn::burn(ctx, amount)
     } 
 
+    /// 使用许可额度代owner销毁WUSD代币
+    pub fn burn_from(ctx: Context<BurnFrom>, amount: u64) -> Result<()> {
+        instructions::burn::burn_from(ctx, amount)
+    }
+
+    /// 授予角色
+    pub fn grant_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+        instructions::role::grant_role(ctx, role, member)
+    }
+
+    /// 撤销角色
+    pub fn revoke_role(ctx: Context<ManageRole>, role: Role, member: Pubkey) -> Result<()> {
+        instructions::role::revoke_role(ctx, role, member)
+    }
+
+    /// 放弃自己持有的角色
+    pub fn renounce_role(ctx: Context<RenounceRole>, role: Role) -> Result<()> {
+        instructions::role::renounce_role(ctx, role)
+    }
+
+    /// 设置角色的管理角色
+    pub fn set_role_admin(ctx: Context<ManageRole>, role: Role, admin_role: Option<Role>) -> Result<()> {
+        instructions::role::set_role_admin(ctx, role, admin_role)
+    }
+
+    /// 提议新的管理员
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::admin::propose_admin(ctx, new_admin)
+    }
+
+    /// 接受管理员转移
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::admin::accept_admin(ctx)
+    }
+
+    /// 取消管理员转移
+    pub fn cancel_admin_transfer(ctx: Context<ProposeAdmin>) -> Result<()> {
+        instructions::admin::cancel_admin_transfer(ctx)
+    }
+
+    /// 将旧版权限状态账户迁移为当前布局
+    pub fn migrate_authority_state(ctx: Context<MigrateAuthorityState>) -> Result<()> {
+        instructions::admin::migrate_authority_state(ctx)
+    }
+
+    /// 将旧版铸币状态账户迁移为当前布局
+    pub fn migrate_mint_state(ctx: Context<MigrateMintState>) -> Result<()> {
+        instructions::mint::migrate_mint_state(ctx)
+    }
+
+    /// 配置铸币者及其铸币额度
+    pub fn configure_minter(ctx: Context<ConfigureMinter>, minter: Pubkey, allowance: u64) -> Result<()> {
+        instructions::minter::configure_minter(ctx, minter, allowance)
+    }
+
+    /// 增加铸币者的铸币额度
+    pub fn increment_minter_allowance(ctx: Context<UpdateMinter>, minter: Pubkey, amount: u64) -> Result<()> {
+        instructions::minter::increment_minter_allowance(ctx, minter, amount)
+    }
+
+    /// 移除铸币者
+    pub fn remove_minter(ctx: Context<RemoveMinter>, minter: Pubkey) -> Result<()> {
+        instructions::minter::remove_minter(ctx, minter)
+    }
+
+    /// 将钱包地址列入黑名单
+    pub fn blacklist(ctx: Context<Blacklist>, account: Pubkey) -> Result<()> {
+        instructions::blacklist::blacklist(ctx, account)
+    }
+
+    /// 将钱包地址移出黑名单
+    pub fn unblacklist(ctx: Context<Unblacklist>, account: Pubkey) -> Result<()> {
+        instructions::blacklist::unblacklist(ctx, account)
+    }
+
+    /// 更新代币元数据
+    pub fn update_metadata(ctx: Context<UpdateMetadata>, field: MetadataField, value: String) -> Result<()> {
+        instructions::metadata::update_metadata(ctx, field, value)
+    }
+
+    /// 更新转账手续费率
+    pub fn set_transfer_fee(ctx: Context<SetTransferFee>, basis_points: u16, maximum_fee: u64) -> Result<()> {
+        instructions::fee::set_transfer_fee(ctx, basis_points, maximum_fee)
+    }
+
+    /// 归集并提取扣留的手续费到国库账户
+    pub fn withdraw_withheld_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawWithheldFees<'info>>,
+    ) -> Result<()> {
+        instructions::fee::withdraw_withheld_fees(ctx)
+    }
+
+    /// 救援误转入程序账户的其他代币
+    pub fn rescue_tokens(ctx: Context<RescueTokens>, amount: u64) -> Result<()> {
+        instructions::rescue::rescue_tokens(ctx, amount)
+    }
+
+    /// 开启或关闭白名单（闭环）模式
+    pub fn set_allowlist_mode(ctx: Context<SetAllowlistMode>, enabled: bool) -> Result<()> {
+        instructions::allowlist::set_allowlist_mode(ctx, enabled)
+    }
+
+    /// 批量加入白名单
+    pub fn add_to_allowlist<'info>(
+        ctx: Context<'_, '_, '_, 'info, ManageAllowlist<'info>>,
+        accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::allowlist::add_to_allowlist(ctx, accounts)
+    }
+
+    /// 批量移出白名单
+    pub fn remove_from_allowlist<'info>(
+        ctx: Context<'_, '_, '_, 'info, ManageAllowlist<'info>>,
+        accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::allowlist::remove_from_allowlist(ctx, accounts)
+    }
+
+    /// 创建或更新钱包的KYC记录
+    pub fn set_kyc_record(
+        ctx: Context<SetKycRecord>,
+        account: Pubkey,
+        tier: u8,
+        expires_at: i64,
+        jurisdiction: [u8; 2],
+    ) -> Result<()> {
+        instructions::kyc::set_kyc_record(ctx, account, tier, expires_at, jurisdiction)
+    }
+
+    /// 删除钱包的KYC记录
+    pub fn remove_kyc_record(ctx: Context<RemoveKycRecord>, account: Pubkey) -> Result<()> {
+        instructions::kyc::remove_kyc_record(ctx, account)
+    }
+
+    /// 设置KYC等级的转账及余额限额
+    pub fn set_kyc_tier_limits(
+        ctx: Context<SetKycTierLimits>,
+        tier: u8,
+        max_single_transfer: Option<u64>,
+        max_daily_outflow: Option<u64>,
+        max_balance: Option<u64>,
+    ) -> Result<()> {
+        instructions::kyc::set_kyc_tier_limits(ctx, tier, max_single_transfer, max_daily_outflow, max_balance)
+    }
+
+    /// 开启或关闭代币账户的入账备注要求
+    pub fn set_memo_required(ctx: Context<SetMemoRequired>, required: bool) -> Result<()> {
+        instructions::memo::set_memo_required(ctx, required)
+    }
+
+    /// 发起赎回请求
+    pub fn request_redemption<'info>(
+        ctx: Context<'_, '_, '_, 'info, RequestRedemption<'info>>,
+        request_id: u64,
+        amount: u64,
+        bank_ref_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::redemption::request_redemption(ctx, request_id, amount, bank_ref_hash)
+    }
+
+    /// 完成赎回
+    pub fn fulfill_redemption(ctx: Context<ResolveRedemption>, settlement_id: String) -> Result<()> {
+        instructions::redemption::fulfill_redemption(ctx, settlement_id)
+    }
+
+    /// 拒绝赎回
+    pub fn reject_redemption<'info>(ctx: Context<'_, '_, '_, 'info, ResolveRedemption<'info>>) -> Result<()> {
+        instructions::redemption::reject_redemption(ctx)
+    }
+
     /// 添加操作员
-    pub fn add_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
-        instructions::operator::add_operator(ctx, operator)
+    pub fn add_operator(
+        ctx: Context<AddOperator>,
+        operator: Pubkey,
+        label: String,
+        expires_at: i64,
+        permissions: u32,
+    ) -> Result<()> {
+        instructions::operator::add_operator(ctx, operator, label, expires_at, permissions)
+    }
+
+    /// 更新操作员权限
+    pub fn update_operator_permissions(
+        ctx: Context<UpdateOperatorPermissions>,
+        operator: Pubkey,
+        permissions: u32,
+    ) -> Result<()> {
+        instructions::operator::update_operator_permissions(ctx, operator, permissions)
     }
 
     /// 移除操作员
-    pub fn remove_operator(ctx: Context<ManageOperator>, operator: Pubkey) -> Result<()> {
+    pub fn remove_operator(ctx: Context<RemoveOperator>, operator: Pubkey) -> Result<()> {
         instructions::operator::remove_operator(ctx, operator)
     }
 
+    /// 将旧版全局访问注册表迁移为按mint划分的注册表
+    pub fn migrate_access_registry<'info>(
+        ctx: Context<'_, '_, '_, 'info, MigrateAccessRegistry<'info>>,
+    ) -> Result<()> {
+        instructions::operator::migrate_access_registry(ctx)
+    }
+
     pub fn initialize_freeze_state(ctx: Context<InitializeFreezeState>) -> Result<()> {
         instructions::freeze::initialize_freeze_state(ctx)
     }
@@ -188,6 +484,11 @@ pub mod wusd_token {
     pub fn unfreeze_account(ctx: Context<UnfreezeAccount>) -> Result<()> {
         instructions::freeze::unfreeze_account(ctx) 
     } 
+
+    /// 清除被冻结账户的全部余额
+    pub fn wipe_frozen_account<'info>(ctx: Context<'_, '_, '_, 'info, WipeFrozenAccount<'info>>) -> Result<()> {
+        instructions::freeze::wipe_frozen_account(ctx)
+    }
 }
 
 #[derive(Accounts)]
@@ -210,7 +511,8 @@ pub struct InitializePdaOnly<'info> {
     /// 代币铸币账户 - 注意这里不使用init约束，因为账户已经存在
     #[account(
         mut,
-        owner = TOKEN_PROGRAM_ID
+        owner = TOKEN_PROGRAM_ID,
+        constraint = token_mint.decimals == decimals @ WusdError::InvalidDecimals
     )]
     pub token_mint: InterfaceAccount<'info, Mint>,
     
@@ -255,15 +557,9 @@ pub struct Initialize<'info> {
     )]
     pub authority_state: Account<'info, AuthorityState>,
 
-    /// 代币铸币账户
-    #[account(
-        init,
-        payer = authority,
-        mint::decimals = decimals,
-        mint::authority = authority.key(),
-        owner = TOKEN_PROGRAM_ID
-    )]
-    pub token_mint: InterfaceAccount<'info, Mint>,
+    /// 代币铸币账户，由程序创建并初始化扩展
+    #[account(mut)]
+    pub token_mint: Signer<'info>,
     
     /// 铸币状态账户
     #[account(
@@ -293,12 +589,21 @@ pub struct Initialize<'info> {
 pub struct InitializeAccessRegistry<'info> {
     #[account(mut)]
     pub authority: Signer<'info>, 
+
+    pub token_mint: InterfaceAccount<'info, anchor_spl::token_interface::Mint>,
+
+    #[account(
+        seeds = [b"authority", token_mint.key().as_ref()],
+        bump,
+        constraint = authority_state.admin == authority.key() @ WusdError::Unauthorized
+    )]
+    pub authority_state: Account<'info, AuthorityState>,
      
     #[account(
         init,
         payer = authority, 
         space = AccessRegistryState::SIZE,
-        seeds = [b"access_registry"],
+        seeds = [b"access_registry", token_mint.key().as_ref()],
         bump
     )]
     pub access_registry: Account<'info, AccessRegistryState>,
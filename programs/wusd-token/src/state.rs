@@ -1,34 +1,66 @@
 use anchor_lang::prelude::*;
-use crate::access::AccessLevel;
+use crate::access::Role;
 use crate::error::WusdError;
 
-/// 授权额度状态账户，存储代币授权信息
+/// 授权额度状态账户，每个mint上owner对spender的唯一授权记录
+///
+/// `approve`等直接授权和EIP-2612兼容的签名许可都写入该账户，
+/// `transfer_from`/`burn_from`从该账户扣减额度
 #[account]
 pub struct AllowanceState {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
     /// 代币所有者地址
     pub owner: Pubkey,
     /// 被授权者地址
     pub spender: Pubkey,
     /// 授权额度
     pub amount: u64,
+    /// 签名许可随机数，用于防止重放攻击
+    pub nonce: u64,
+    /// 过期时间
+    pub expiration: i64,
+    /// 授权范围
+    pub scope: PermitScope,
+    /// PDA bump
+    pub bump: u8,
 }
 
 impl AllowanceState {
     /// 授权额度状态账户大小
-    pub const SIZE: usize = 8 + 32 + 32 + 8;
+    pub const SIZE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + PermitScope::SIZE + 1;
 
-    /// 初始化授权状态
+    /// 初始化授权状态，额度为0且不过期
+    /// * `mint` - 代币铸币账户
     /// * `owner` - 代币所有者
     /// * `spender` - 被授权者
-    /// * `amount` - 授权金额
-    pub fn initialize(owner: Pubkey, spender: Pubkey, amount: u64) -> Self {
+    /// * `bump` - PDA bump
+    pub fn initialize(mint: Pubkey, owner: Pubkey, spender: Pubkey, bump: u8) -> Self {
         Self {
+            mint,
             owner,
             spender,
-            amount,
+            amount: 0,
+            nonce: 0,
+            expiration: i64::MAX,
+            scope: PermitScope::ALLOWANCE,
+            bump,
         }
     }
 
+    /// 是否为刚创建、尚未写入过的账户
+    pub fn is_uninitialized(&self) -> bool {
+        self.owner == Pubkey::default()
+    }
+
+    /// 设置授权额度，同时清除签名许可附带的期限和范围限制
+    /// * `amount` - 授权金额
+    pub fn approve(&mut self, amount: u64) {
+        self.amount = amount;
+        self.expiration = i64::MAX;
+        self.scope = PermitScope::ALLOWANCE;
+    }
+
     /// 增加授权额度
     /// * `added_value` - 增加的额度
     pub fn increase_allowance(&mut self, added_value: u64) -> Result<()> {
@@ -52,40 +84,28 @@ impl AllowanceState {
         require!(self.amount >= amount, crate::error::WusdError::InvalidAmount);
         Ok(())
     }
-}
 
-/// 签名许可状态账户，用于EIP-2612兼容的签名授权
-#[account]
-pub struct PermitState {
-    /// 所有者地址
-    pub owner: Pubkey,
-    /// 被授权者地址
-    pub spender: Pubkey,
-    /// 随机数，用于防止重放攻击
-    pub nonce: u64,
-    /// 授权额度
-    pub amount: u64,
-    /// 过期时间
-    pub expiration: i64,
-    /// PDA bump
-    pub bump: u8,
-}
+    /// 验证许可允许转账
+    pub fn validate_transfer_scope(&self) -> Result<()> {
+        require!(self.scope.allows_transfer(), crate::error::WusdError::InvalidPermitScope);
+        Ok(())
+    }
 
-impl PermitState {
-    /// 许可状态账户大小
-    pub const SIZE: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+    /// 验证许可允许销毁
+    pub fn validate_burn_scope(&self) -> Result<()> {
+        require!(self.scope.allows_burn(), crate::error::WusdError::InvalidPermitScope);
+        Ok(())
+    }
 
-    /// 初始化签名许可状态
-    /// * `owner` - 所有者地址
-    pub fn initialize(owner: Pubkey, spender: Pubkey, amount: u64, expiration: i64, bump: u8) -> Self {
-        Self {
-            owner,
-            spender,
-            nonce: 0,
-            amount,
-            expiration,
-            bump,
+    /// 使用授权额度，单次许可使用后即失效
+    /// * `amount` - 使用的额度
+    pub fn consume(&mut self, amount: u64) -> Result<()> {
+        self.amount = self.amount.checked_sub(amount)
+            .ok_or(error!(crate::error::WusdError::InsufficientAllowance))?;
+        if self.scope.one_time {
+            self.amount = 0;
         }
+        Ok(())
     }
 
     /// 增加随机数
@@ -99,29 +119,80 @@ impl PermitState {
         require!(self.nonce == expected_nonce, crate::error::WusdError::InvalidNonce);
         Ok(())
     }
+
+    /// 作废所有小于`new_nonce`的未使用许可
+    /// * `new_nonce` - 新的随机数，必须大于当前随机数
+    pub fn invalidate_nonces(&mut self, new_nonce: u64) -> Result<()> {
+        require!(new_nonce > self.nonce, crate::error::WusdError::InvalidNonce);
+        self.nonce = new_nonce;
+        Ok(())
+    }
+}
+
+/// 许可授权范围
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct PermitScope {
+    /// 单次授权
+    pub one_time: bool,
+    /// 永久授权
+    pub permanent: bool,
+    /// 转账授权
+    pub transfer: bool,
+    /// 销毁授权
+    pub burn: bool,
+    /// 全部授权
+    pub all: bool
+}
+
+impl PermitScope {
+    pub const SIZE: usize = 5;
+
+    /// `approve`等直接授权使用的范围：长期有效，可转账和销毁
+    pub const ALLOWANCE: PermitScope = PermitScope {
+        one_time: false,
+        permanent: true,
+        transfer: true,
+        burn: true,
+        all: false
+    };
+
+    /// 至少授予了一种操作
+    pub fn is_valid(&self) -> bool {
+        self.transfer || self.burn || self.all
+    }
+
+    /// 是否允许转账
+    pub fn allows_transfer(&self) -> bool {
+        self.transfer || self.all
+    }
+
+    /// 是否允许销毁
+    pub fn allows_burn(&self) -> bool {
+        self.burn || self.all
+    }
 }
 
-/// 权限管理状态账户，存储合约的权限配置
+/// 权限管理状态账户，存储合约的管理员，同时作为mint的铸币权限PDA
 #[account]
 pub struct AuthorityState {
-    /// 管理员地址
+    /// 管理员地址，拥有所有角色的管理权
     pub admin: Pubkey,
-    /// 铸币权限地址
-    pub minter: Pubkey,
-    /// 暂停权限地址
-    pub pauser: Pubkey,
+    /// 待接受的新管理员地址
+    pub pending_admin: Option<Pubkey>,
 }
 
 impl AuthorityState {
     /// 权限管理状态账户大小
-    /// discriminator + admin + minter + pauser
-    pub const SIZE: usize = 8 + 32 * 3;
+    /// discriminator + admin + pending_admin
+    pub const SIZE: usize = 8 + 32 + (1 + 32);
+
+    /// 旧版账户数据布局：discriminator + admin + minter + pauser
+    pub const LEGACY_DATA_LEN: usize = 8 + 32 * 3;
 
     pub fn initialize(admin: Pubkey) -> Self {
         Self {
-            admin: admin,
-            minter: admin,
-            pauser: admin,
+            admin,
+            pending_admin: None,
         }
     }
 
@@ -129,13 +200,77 @@ impl AuthorityState {
         self.admin == user
     }
 
-    pub fn is_minter(&self, user: Pubkey) -> bool {
-        self.minter == user
+    pub fn is_pending_admin(&self, user: Pubkey) -> bool {
+        self.pending_admin == Some(user)
+    }
+}
+
+/// 每个角色最多的成员数量
+pub const MAX_ROLE_MEMBERS: usize = 10;
+
+/// 角色状态账户，每个mint的每个角色一个，记录角色成员
+#[account]
+pub struct RoleState {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 角色
+    pub role: Role,
+    /// 可以授予/撤销该角色的管理角色，None表示仅管理员可以管理
+    pub admin_role: Option<Role>,
+    /// 成员列表 (使用固定大小数组代替 Vec 来避免序列化问题)
+    pub members: [Pubkey; MAX_ROLE_MEMBERS],
+    /// 当前成员数量
+    pub member_count: u8,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RoleState {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        1 + // role
+        2 + // admin_role
+        (32 * MAX_ROLE_MEMBERS) + // members array
+        1 + // member_count
+        1; // bump
+
+    /// 是否为刚创建、尚未写入过的账户
+    pub fn is_uninitialized(&self) -> bool {
+        self.mint == Pubkey::default()
+    }
+
+    /// 检查用户是否拥有该角色
+    pub fn has_role(&self, user: Pubkey) -> bool {
+        self.members[..self.member_count as usize].contains(&user)
+    }
+
+    /// 添加成员，返回是否为新增
+    pub fn grant(&mut self, member: Pubkey) -> Result<bool> {
+        if self.has_role(member) {
+            return Ok(false);
+        }
+        require!(
+            (self.member_count as usize) < MAX_ROLE_MEMBERS,
+            WusdError::TooManyRoleMembers
+        );
+        self.members[self.member_count as usize] = member;
+        self.member_count += 1;
+        Ok(true)
     }
 
-    pub fn is_pauser(&self, user: Pubkey) -> bool {
-        self.pauser == user
-    } 
+    /// 移除成员
+    pub fn revoke(&mut self, member: Pubkey) -> Result<()> {
+        let count = self.member_count as usize;
+        let index = self.members[..count]
+            .iter()
+            .position(|m| *m == member)
+            .ok_or(error!(WusdError::RoleMemberNotFound))?;
+        // 用最后一个成员填补空位
+        self.members[index] = self.members[count - 1];
+        self.members[count - 1] = Pubkey::default();
+        self.member_count -= 1;
+        Ok(())
+    }
 }
 
 /// 访问权限注册表状态
@@ -144,94 +279,259 @@ impl AuthorityState {
 pub struct AccessRegistryState {
     /// 管理员地址
     pub authority: Pubkey,
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
     /// 是否已初始化
     pub initialized: bool,
-    /// 操作员列表 (使用固定大小数组代替 Vec 来避免序列化问题)
-    pub operators: [Pubkey; 10],  // 支持最多10个操作员
-    /// 当前操作员数量
-    pub operator_count: u8,
+    /// 当前操作员数量，每个操作员对应一个`OperatorRecord` PDA
+    pub operator_count: u64,
+    /// 是否启用白名单（闭环）模式，启用后仅白名单地址可以接收代币
+    pub allowlist_enabled: bool,
 }
 
 impl AccessRegistryState {
     pub const SIZE: usize = 8 + // discriminator
         32 + // authority
-        4 + // operator_count
-        (32 * 10) + // operators array
-        1; // initialized
+        32 + // mint
+        1 + // initialized
+        8 + // operator_count
+        1; // allowlist_enabled
+
+    /// 旧版全局注册表（种子不含mint）的账户数据布局：
+    /// discriminator + authority + initialized + operators + operator_count
+    pub const LEGACY_DATA_LEN: usize = 8 + 32 + 1 + (32 * 10) + 1;
 
-    pub fn new(authority: Pubkey) -> Self {
+    pub fn new(authority: Pubkey, mint: Pubkey) -> Self {
         Self {
             authority,
-            operator_count: 0,
-            operators: [Pubkey::default(); 10],
+            mint,
             initialized: false,
+            operator_count: 0,
+            allowlist_enabled: false,
+        }
+    }
+
+    /// 检查用户是否具有执行指定操作的权限
+    ///
+    /// * `permission` - 操作所需的权限位，见`Permission`
+    /// * `operator_record` - 用户的操作员记录，非操作员时为None
+    /// * `now` - 当前时间戳，用于判断操作员记录是否过期
+    pub fn has_access(
+        &self,
+        user: Pubkey,
+        permission: u32,
+        operator_record: Option<&OperatorRecord>,
+        now: i64,
+    ) -> bool {
+        // 如果是管理员，允许所有操作
+        if user == self.authority {
+            return true;
         }
+
+        // 检查是否是未过期且具有该权限的操作员
+        operator_record.is_some_and(|record| {
+            record.mint == self.mint
+                && record.operator == user
+                && record.is_active(now)
+                && record.permissions & permission == permission
+        })
+    }
+}
+
+/// 操作员记录，每个(mint, operator)对应一个PDA
+#[account]
+pub struct OperatorRecord {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 操作员地址
+    pub operator: Pubkey,
+    /// 备注名称
+    pub label: String,
+    /// 添加时间
+    pub added_at: i64,
+    /// 添加者
+    pub added_by: Pubkey,
+    /// 过期时间，0表示永不过期
+    pub expires_at: i64,
+    /// 权限位，见`Permission`
+    pub permissions: u32,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl OperatorRecord {
+    /// 备注名称的最大长度
+    pub const MAX_LABEL_LEN: usize = 32;
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // operator
+        (4 + Self::MAX_LABEL_LEN) + // label
+        8 + // added_at
+        32 + // added_by
+        8 + // expires_at
+        4 + // permissions
+        1; // bump
+
+    /// 记录在`now`时是否仍然有效
+    pub fn is_active(&self, now: i64) -> bool {
+        self.expires_at == 0 || now < self.expires_at
     }
+}
 
-    /// 添加操作员
-    pub fn add_operator(&mut self, operator: Pubkey) -> Result<()> {
-        // 检查是否已达到最大操作员数量
+/// 滑动窗口速率限制
+///
+/// `window`被均分为`BUCKETS`个时间桶（如`window`为86400时每桶1小时），用量记入当前时间桶，
+/// 统计时累加当前桶及之前`BUCKETS - 1`个桶，即最近`window`秒内（精度为一个桶长）的用量不超过`limit`。
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct RateLimit {
+    /// 每个窗口允许的最大数量，0表示不限制
+    pub limit: u64,
+    /// 窗口长度（秒）
+    pub window: i64,
+    /// 各时间桶内的用量，按桶序号对`BUCKETS`取模存放
+    pub buckets: [u64; 24],
+    /// 最近一次记录用量的桶序号（时间戳除以桶长）
+    pub last_bucket: i64,
+}
+
+impl RateLimit {
+    /// 每个窗口划分的时间桶数量
+    pub const BUCKETS: usize = 24;
+
+    pub const SIZE: usize = 8 + // limit
+        8 + // window
+        8 * Self::BUCKETS + // buckets
+        8; // last_bucket
+
+    /// 更新限制配置，窗口长度变化时清空已记录的用量
+    /// * `limit` - 每个窗口允许的最大数量，0表示不限制
+    /// * `window` - 窗口长度（秒），须为`BUCKETS`的整数倍
+    pub fn configure(&mut self, limit: u64, window: i64) -> Result<()> {
         require!(
-            self.operator_count < 10,
-            WusdError::TooManyOperators
+            limit == 0 || (window > 0 && window % Self::BUCKETS as i64 == 0),
+            WusdError::InvalidRateLimit
         );
+        if window != self.window {
+            self.buckets = [0; Self::BUCKETS];
+            self.last_bucket = 0;
+        }
+        self.limit = limit;
+        self.window = window;
+        Ok(())
+    }
 
-        // 检查操作员是否已存在
-        for i in 0..self.operator_count as usize {
-            if self.operators[i] == operator {
-                return Ok(());  // 操作员已存在，直接返回
-            }
+    /// 记录一次用量，超过限制时返回错误
+    /// * `amount` - 本次用量
+    /// * `now` - 当前时间戳
+    pub fn consume(&mut self, amount: u64, now: i64) -> Result<()> {
+        if self.limit == 0 {
+            return Ok(());
         }
 
-        // 添加新操作员
-        self.operators[self.operator_count as usize] = operator;
-        self.operator_count += 1;
+        // 清空上次记录之后已滑出窗口的时间桶
+        let bucket_len = self.window / Self::BUCKETS as i64;
+        let current = now.div_euclid(bucket_len);
+        let expired = (current - self.last_bucket).clamp(0, Self::BUCKETS as i64);
+        for bucket in (current - expired + 1)..=current {
+            self.buckets[bucket.rem_euclid(Self::BUCKETS as i64) as usize] = 0;
+        }
+        self.last_bucket = self.last_bucket.max(current);
+
+        let used = self.buckets.iter().try_fold(amount, |total, used| total.checked_add(*used))
+            .ok_or(error!(WusdError::MintRateLimitExceeded))?;
+        require!(used <= self.limit, WusdError::MintRateLimitExceeded);
+
+        let slot = &mut self.buckets[current.rem_euclid(Self::BUCKETS as i64) as usize];
+        *slot = slot.checked_add(amount)
+            .ok_or(error!(WusdError::MintRateLimitExceeded))?;
         Ok(())
     }
+}
 
-    /// 移除操作员
-    pub fn remove_operator(&mut self, operator: Pubkey) -> Result<()> {
-        let mut found = false;
-        for i in 0..self.operator_count as usize {
-            if self.operators[i] == operator {
-                // 找到要移除的操作员
-                found = true;
-                // 将后面的操作员向前移动
-                for j in i..self.operator_count as usize - 1 {
-                    self.operators[j] = self.operators[j + 1];
-                }
-                // 清除最后一个位置
-                self.operators[self.operator_count as usize - 1] = Pubkey::default();
-                self.operator_count -= 1;
-                break;
-            }
-        }
+/// 铸币者状态账户，每个mint的每个铸币者一个，记录剩余铸币额度
+#[account]
+pub struct MinterState {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 铸币者地址
+    pub minter: Pubkey,
+    /// 剩余铸币额度
+    pub allowance: u64,
+    /// PDA bump
+    pub bump: u8,
+}
 
-        require!(found, WusdError::OperatorNotFound);
+impl MinterState {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // minter
+        8 + // allowance
+        1; // bump
+
+    /// 增加铸币额度
+    /// * `amount` - 增加的额度
+    pub fn increase_allowance(&mut self, amount: u64) -> Result<()> {
+        self.allowance = self.allowance.checked_add(amount)
+            .ok_or(error!(WusdError::InvalidAmount))?;
         Ok(())
     }
 
-    /// 检查是否有访问权限
-    pub fn has_access(&self, user: Pubkey, level: AccessLevel) -> bool {
-        // 如果是 Credit 操作（接收代币），直接允许
-        if matches!(level, AccessLevel::Credit) {
-            return true;
-        }
+    /// 扣减铸币额度
+    /// * `amount` - 铸币数量
+    pub fn consume_allowance(&mut self, amount: u64) -> Result<()> {
+        self.allowance = self.allowance.checked_sub(amount)
+            .ok_or(error!(WusdError::MinterAllowanceExceeded))?;
+        Ok(())
+    }
+}
 
-        // 如果是管理员，允许所有操作
-        if user == self.authority {
-            return true;
-        }
+/// 单个铸币者的铸币速率限制，由管理员设置
+///
+/// 独立于`MinterState`，移除并重新配置铸币者不会重置限制及已用量
+#[account]
+pub struct MinterRateLimit {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 铸币者地址
+    pub minter: Pubkey,
+    /// 速率限制
+    pub rate_limit: RateLimit,
+    /// PDA bump
+    pub bump: u8,
+}
 
-        // 检查是否是操作员
-        for i in 0..self.operator_count as usize {
-            if self.operators[i] == user {
-                return true;
-            }
-        }
+impl MinterRateLimit {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // minter
+        RateLimit::SIZE + // rate_limit
+        1; // bump
+}
 
-        false
-    }
+/// 黑名单条目，每个mint的每个钱包地址一个
+/// 条目存在即表示该地址被列入黑名单，移出黑名单时关闭账户
+#[account]
+pub struct BlacklistEntry {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 被列入黑名单的钱包地址
+    pub account: Pubkey,
+    /// 执行操作的黑名单管理员
+    pub blacklisted_by: Pubkey,
+    /// 列入黑名单的时间
+    pub blacklisted_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl BlacklistEntry {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // account
+        32 + // blacklisted_by
+        8 + // blacklisted_at
+        1; // bump
 }
 
 /// 铸币状态账户，存储代币铸造相关信息
@@ -241,12 +541,96 @@ pub struct MintState {
     pub mint: Pubkey,
     /// 代币精度
     pub decimals: u8,
+    /// 最大供应量
+    pub max_supply: u64,
+    /// 全局铸币速率限制
+    pub mint_rate_limit: RateLimit,
 }
 
 impl MintState {
     pub const SIZE: usize = 8 + // discriminator
         32 + // mint
-        1;  // decimals
+        1 + // decimals
+        8 + // max_supply
+        RateLimit::SIZE;  // mint_rate_limit
+
+    /// 旧版账户数据布局：discriminator + mint + decimals
+    pub const LEGACY_DATA_LEN: usize = 8 + 32 + 1;
+
+    /// 验证铸币后的总供应量不超过上限
+    /// * `current_supply` - 当前总供应量
+    /// * `amount` - 铸币数量
+    pub fn validate_supply(&self, current_supply: u64, amount: u64) -> Result<()> {
+        let new_supply = current_supply.checked_add(amount)
+            .ok_or(error!(WusdError::MaxSupplyExceeded))?;
+        require!(new_supply <= self.max_supply, WusdError::MaxSupplyExceeded);
+        Ok(())
+    }
+}
+
+/// 赎回请求状态
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RedemptionStatus {
+    /// 已托管，等待处理
+    Pending,
+    /// 已销毁并完成法币兑付
+    Fulfilled,
+    /// 已拒绝并退回代币
+    Rejected,
+}
+
+/// 赎回请求账户，记录托管的代币与对应的法币兑付信息
+#[account]
+pub struct RedemptionRequest {
+    /// 代币铸币账户地址
+    pub mint: Pubkey,
+    /// 发起赎回的代币所有者
+    pub owner: Pubkey,
+    /// 代币来源账户，拒绝时退回该账户
+    pub token_account: Pubkey,
+    /// 请求编号，由发起者指定
+    pub request_id: u64,
+    /// 赎回数量
+    pub amount: u64,
+    /// 银行收款信息的哈希
+    pub bank_ref_hash: [u8; 32],
+    /// 请求状态
+    pub status: RedemptionStatus,
+    /// 兑付完成后的结算编号
+    pub settlement_id: Option<String>,
+    /// 发起时间
+    pub requested_at: i64,
+    /// 处理时间
+    pub resolved_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl RedemptionRequest {
+    /// 结算编号的最大长度
+    pub const MAX_SETTLEMENT_ID_LEN: usize = 64;
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // owner
+        32 + // token_account
+        8 + // request_id
+        8 + // amount
+        32 + // bank_ref_hash
+        1 + // status
+        (1 + 4 + Self::MAX_SETTLEMENT_ID_LEN) + // settlement_id
+        8 + // requested_at
+        8 + // resolved_at
+        1; // bump
+
+    /// 验证请求仍在等待处理
+    pub fn validate_pending(&self) -> Result<()> {
+        require!(
+            self.status == RedemptionStatus::Pending,
+            WusdError::RedemptionNotPending
+        );
+        Ok(())
+    }
 }
 
 /// 暂停状态账户，用于控制合约的暂停/恢复
@@ -300,4 +684,166 @@ impl FreezeState {
     pub fn unfreeze(&mut self) {
         self.is_frozen = false;
     }
-}
\ No newline at end of file
+}
+
+/// 白名单条目，每个mint的每个钱包地址一个
+/// 白名单模式下，条目存在即表示该地址可以接收代币，移出白名单时关闭账户
+#[account]
+pub struct AllowlistEntry {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 白名单钱包地址
+    pub account: Pubkey,
+    /// 执行操作的白名单管理员
+    pub added_by: Pubkey,
+    /// 加入白名单的时间
+    pub added_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl AllowlistEntry {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // account
+        32 + // added_by
+        8 + // added_at
+        1; // bump
+}
+
+/// KYC记录，每个mint的每个钱包地址一个
+/// 记录不存在的钱包视为未验证等级
+#[account]
+pub struct KycRecord {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 钱包地址
+    pub account: Pubkey,
+    /// KYC等级，如0未验证、1零售、2机构
+    pub tier: u8,
+    /// 过期时间，0表示永不过期
+    pub expires_at: i64,
+    /// 司法管辖区代码（ISO 3166-1 alpha-2）
+    pub jurisdiction: [u8; 2],
+    /// 最后更新者
+    pub updated_by: Pubkey,
+    /// 最后更新时间
+    pub updated_at: i64,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl KycRecord {
+    /// 未验证等级
+    pub const TIER_UNVERIFIED: u8 = 0;
+    /// 等级数量，等级取值为`0..TIER_COUNT`；转账钩子的额外账户列表为每个等级包含一个限额账户
+    pub const TIER_COUNT: u8 = 3;
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // account
+        1 + // tier
+        8 + // expires_at
+        2 + // jurisdiction
+        32 + // updated_by
+        8 + // updated_at
+        1; // bump
+
+    /// 记录在`now`时是否已过期
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+}
+
+/// 钱包的每日流出累计，每个mint的每个钱包地址一个
+///
+/// 独立于`KycRecord`，在发送方等级设置了每日流出上限时由转账按需创建，
+/// 未验证钱包同样受限，删除并重新创建KYC记录也不会重置当日累计
+#[account]
+pub struct KycOutflow {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 钱包地址
+    pub account: Pubkey,
+    /// 当日流出累计
+    pub daily_outflow: DailyOutflow,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl KycOutflow {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // account
+        DailyOutflow::SIZE + // daily_outflow
+        1; // bump
+}
+
+/// 按UTC自然日（Unix时间戳整除86400）累计的流出数量，次日首笔流出时清零
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DailyOutflow {
+    /// 当日已流出数量
+    pub amount: u64,
+    /// `amount`所属的日期（自Unix纪元起的天数）
+    pub day: i64,
+}
+
+impl DailyOutflow {
+    /// 每日秒数
+    pub const SECONDS_PER_DAY: i64 = 86_400;
+
+    pub const SIZE: usize = 8 + // amount
+        8; // day
+
+    /// `now`所在日期已流出的数量
+    pub fn today(&self, now: i64) -> u64 {
+        if self.day == now / Self::SECONDS_PER_DAY {
+            self.amount
+        } else {
+            0
+        }
+    }
+
+    /// 累计当日流出，累计后超过`max`时返回错误
+    /// * `amount` - 本次流出数量
+    /// * `max` - 每日流出上限
+    /// * `now` - 当前时间戳
+    pub fn record(&mut self, amount: u64, max: u64, now: i64) -> Result<()> {
+        let total = self
+            .today(now)
+            .checked_add(amount)
+            .filter(|total| *total <= max)
+            .ok_or(WusdError::DailyOutflowLimitExceeded)?;
+        self.amount = total;
+        self.day = now / Self::SECONDS_PER_DAY;
+        Ok(())
+    }
+}
+
+/// KYC等级限额，每个mint的每个等级一个，None表示不限制
+/// 限额账户不存在的等级不受限制
+#[account]
+pub struct KycTierLimits {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// KYC等级
+    pub tier: u8,
+    /// 单笔转账上限
+    pub max_single_transfer: Option<u64>,
+    /// 每日流出上限
+    pub max_daily_outflow: Option<u64>,
+    /// 接收代币账户的余额上限，按单个代币账户计算，不汇总同一钱包的其他代币账户
+    pub max_balance: Option<u64>,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl KycTierLimits {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        1 + // tier
+        (1 + 8) + // max_single_transfer
+        (1 + 8) + // max_daily_outflow
+        (1 + 8) + // max_balance
+        1; // bump
+}
@@ -1,16 +1,30 @@
 use anchor_lang::prelude::*;
-use crate::access::AccessLevel;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::token_2022::TransferChecked;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
 use crate::error::WusdError;
-use crate::state::{PauseState, AccessRegistryState}; 
+use crate::state::{PauseState, AccessRegistryState, DailyOutflow, KycOutflow, KycRecord, KycTierLimits, OperatorRecord};
+use spl_token_2022::extension::{
+    permanent_delegate::get_permanent_delegate,
+    transfer_fee::{TransferFeeConfig, MAX_FEE_BASIS_POINTS},
+    BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+};
+use spl_token_2022::error::TokenError;
 
 /// 检查用户是否具有执行操作的权限
 /// 
 /// # 参数
 /// * `user` - 用户地址
-/// * `is_debit` - 是否为扣款操作
+/// * `permission` - 操作所需的操作员权限位，见`Permission`
 /// * `amount` - 操作金额（可选）
 /// * `pause_state` - 暂停状态
 /// * `access_registry` - 访问权限注册表（可选）
+/// * `operator_record` - 用户的操作员记录（可选），非操作员时为None
 /// 
 /// # 错误
 /// * `WusdError::ContractPaused` - 合约已暂停
@@ -18,10 +32,11 @@ use crate::state::{PauseState, AccessRegistryState};
 /// * `WusdError::AccessDenied` - 访问被拒绝
 pub fn require_has_access(
     user: Pubkey,
-    is_debit: bool,
+    permission: u32,
     amount: Option<u64>,
     pause_state: &PauseState,
     access_registry: Option<&AccessRegistryState>,
+    operator_record: Option<&OperatorRecord>,
 ) -> Result<()> {
     // 确保合约未暂停
     pause_state.validate_not_paused()?;
@@ -34,16 +49,523 @@ pub fn require_has_access(
     // 验证访问权限
     if let Some(registry) = access_registry {
         require!(registry.initialized, WusdError::AccessRegistryNotInitialized);
-        let required_level = if is_debit {
-            AccessLevel::Debit
-        } else {
-            AccessLevel::Credit
-        };
+        let now = Clock::get()?.unix_timestamp;
         require!(
-            registry.has_access(user, required_level),
+            registry.has_access(user, permission, operator_record, now),
             WusdError::AccessDenied
         );
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Ed25519程序指令头部大小（签名数量 + 填充字节）
+const ED25519_HEADER_SIZE: usize = 2;
+/// 单个签名偏移量结构的大小（7个u16）
+const ED25519_OFFSETS_SIZE: usize = 14;
+
+/// 校验当前交易中、位于本指令之前的Ed25519程序指令是否由指定公钥对消息签名
+///
+/// # 参数
+/// * `instructions_sysvar` - 指令sysvar账户
+/// * `signer` - 期望的签名者公钥
+/// * `message` - 期望被签名的消息字节
+/// * `signature` - 期望的签名
+///
+/// # 错误
+/// * `WusdError::MissingSignatureVerification` - 未找到匹配的Ed25519指令
+pub fn verify_ed25519_instruction(
+    instructions_sysvar: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+
+    for index in 0..current_index {
+        let ix = load_instruction_at_checked(index as usize, instructions_sysvar)?;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        if ed25519_instruction_matches(&ix.data, signer, message, signature) {
+            return Ok(());
+        }
+    }
+
+    err!(WusdError::MissingSignatureVerification)
+}
+
+/// 解析Ed25519指令数据，判断其中是否包含与期望完全一致的签名、公钥和消息
+fn ed25519_instruction_matches(
+    data: &[u8],
+    signer: &Pubkey,
+    message: &[u8],
+    signature: &[u8; 64],
+) -> bool {
+    if data.len() < ED25519_HEADER_SIZE {
+        return false;
+    }
+    let num_signatures = data[0] as usize;
+
+    for i in 0..num_signatures {
+        let start = ED25519_HEADER_SIZE + i * ED25519_OFFSETS_SIZE;
+        let Some(offsets) = data.get(start..start + ED25519_OFFSETS_SIZE) else {
+            return false;
+        };
+        let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]) as usize;
+
+        let signature_offset = read_u16(0);
+        let signature_ix_index = read_u16(2);
+        let public_key_offset = read_u16(4);
+        let public_key_ix_index = read_u16(6);
+        let message_offset = read_u16(8);
+        let message_size = read_u16(10);
+        let message_ix_index = read_u16(12);
+
+        // 签名、公钥和消息必须全部位于该Ed25519指令自身的数据中
+        let this_ix = u16::MAX as usize;
+        if signature_ix_index != this_ix
+            || public_key_ix_index != this_ix
+            || message_ix_index != this_ix
+        {
+            continue;
+        }
+
+        let matches = data.get(signature_offset..signature_offset + 64) == Some(&signature[..])
+            && data.get(public_key_offset..public_key_offset + 32) == Some(signer.as_ref())
+            && data.get(message_offset..message_offset + message_size) == Some(message);
+        if matches {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// mint账户启用的Token-2022扩展
+const MINT_EXTENSIONS: [ExtensionType; 2] = [
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::PermanentDelegate,
+];
+
+/// WUSD转账钩子程序ID
+pub const TRANSFER_HOOK_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("D6xjtPxq19pPKCVBuw6s61JDUa3sULeFFPay1EJhsk43");
+
+/// TransferHook和MetadataPointer扩展在mint中占用的长度：类型(2) + 长度(2) + authority(32) + 地址(32)
+/// spl-token-2022 0.6.1尚不支持这两个扩展，需手动计算并构造指令；
+/// 依赖升级前，生成的mint由测试"Enforce the transfer hook on direct Token-2022 transfers"
+/// 通过@solana/spl-token解析校验
+const ADDRESS_EXTENSION_LEN: usize = 2 + 2 + 32 + 32;
+/// Token-2022 TransferHookExtension指令序号，紧随0.6.1中最后的InitializePermanentDelegate(35)
+const TRANSFER_HOOK_EXTENSION_INSTRUCTION: u8 = 36;
+/// Token-2022 MetadataPointerExtension指令序号
+const METADATA_POINTER_EXTENSION_INSTRUCTION: u8 = 39;
+/// 扩展指令下的Initialize子指令序号
+const EXTENSION_INITIALIZE: u8 = 0;
+
+/// 创建启用扩展的Token-2022 mint账户
+///
+/// 铸币权限、冻结权限、永久委托和手续费权限均设置为`authority`（authority_state PDA），
+/// 将转账钩子注册为`TRANSFER_HOOK_PROGRAM_ID`，并将元数据指针指向mint自身。
+/// 手续费扩展总是启用，费率为0时不收取手续费。
+/// 扩展必须在InitializeMint之前初始化，因此无法对已存在的mint追加；
+/// 元数据本身需在InitializeMint之后写入
+///
+/// # 参数
+/// * `payer` - 支付租金的账户
+/// * `mint` - 待创建的mint账户（需签名）
+/// * `authority` - 铸币权限、冻结权限及永久委托
+/// * `decimals` - 小数位数
+/// * `transfer_fee_basis_points` - 转账手续费率（基点）
+/// * `maximum_fee` - 单笔转账手续费上限
+#[allow(clippy::too_many_arguments)]
+pub fn create_mint_with_extensions<'info>(
+    payer: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    authority: &Pubkey,
+    decimals: u8,
+    transfer_fee_basis_points: u16,
+    maximum_fee: u64,
+    system_program: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require!(
+        transfer_fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        WusdError::InvalidTransferFee
+    );
+
+    let space = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&MINT_EXTENSIONS)
+        + 2 * ADDRESS_EXTENSION_LEN;
+    anchor_lang::system_program::create_account(
+        CpiContext::new(
+            system_program.clone(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.clone(),
+                to: mint.clone(),
+            },
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        token_program.key,
+    )?;
+
+    invoke(
+        &spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+            token_program.key,
+            mint.key,
+            Some(authority),
+            Some(authority),
+            transfer_fee_basis_points,
+            maximum_fee,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_permanent_delegate(
+            token_program.key,
+            mint.key,
+            authority,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    // 以下扩展须在已知扩展之后初始化，spl-token-2022 0.6.1遍历扩展时无法识别这些类型
+    invoke(
+        &initialize_address_extension(
+            token_program.key,
+            mint.key,
+            TRANSFER_HOOK_EXTENSION_INSTRUCTION,
+            authority,
+            &TRANSFER_HOOK_PROGRAM_ID,
+        ),
+        std::slice::from_ref(mint),
+    )?;
+
+    invoke(
+        &initialize_address_extension(
+            token_program.key,
+            mint.key,
+            METADATA_POINTER_EXTENSION_INSTRUCTION,
+            authority,
+            mint.key,
+        ),
+        std::slice::from_ref(mint),
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(
+            token_program.key,
+            mint.key,
+            authority,
+            Some(authority),
+            decimals,
+        )?,
+        std::slice::from_ref(mint),
+    )?;
+
+    Ok(())
+}
+
+/// 构造TransferHook、MetadataPointer这类由authority和地址组成的扩展的Initialize指令
+fn initialize_address_extension(
+    token_program_id: &Pubkey,
+    mint: &Pubkey,
+    extension_instruction: u8,
+    authority: &Pubkey,
+    address: &Pubkey,
+) -> Instruction {
+    let mut data = vec![extension_instruction, EXTENSION_INITIALIZE];
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(address.as_ref());
+    Instruction {
+        program_id: *token_program_id,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    }
+}
+
+/// 与`token_2022::transfer_checked`相同，但会将`remaining_accounts`附加到指令中，
+/// 使Token-2022能够把转账钩子所需的额外账户传给钩子程序
+pub fn transfer_checked_with_hook<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    let mut ix = spl_token_2022::instruction::transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from.key,
+        ctx.accounts.mint.key,
+        ctx.accounts.to.key,
+        ctx.accounts.authority.key,
+        &[],
+        amount,
+        decimals,
+    )?;
+    ix.accounts.extend(ctx.remaining_accounts.iter().map(|account| AccountMeta {
+        pubkey: *account.key,
+        is_signer: account.is_signer,
+        is_writable: account.is_writable,
+    }));
+
+    let mut account_infos = vec![
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+    ];
+    account_infos.extend(ctx.remaining_accounts);
+    invoke_signed(&ix, &account_infos, ctx.signer_seeds)?;
+    Ok(())
+}
+
+/// 补足账户租金，用于Token-2022扩展数据增长后保持免租
+pub fn top_up_rent<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(account.data_len());
+    let current = account.lamports();
+    if required > current {
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            required - current,
+        )?;
+    }
+    Ok(())
+}
+
+/// 计算当前epoch下转账`amount`时Token-2022扣留的手续费，mint未启用手续费扩展时为0
+///
+/// 扩展数据无法解析等其他错误会被返回，而不是按无手续费处理
+pub fn calculate_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    // 未启用任何扩展的mint没有TLV数据
+    if state.get_tlv_data().is_empty() {
+        return Ok(0);
+    }
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(WusdError::InvalidAmount)),
+        Err(ProgramError::Custom(code)) if code == TokenError::ExtensionNotFound as u32 => Ok(0),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// 转账备注的最大字节数
+pub const MAX_MEMO_LEN: usize = 256;
+
+/// 通过SPL Memo程序记录转账备注，备注由`signer`签名
+///
+/// 需在转账CPI之前调用，以满足接收方启用的memo-transfer扩展
+pub fn log_memo<'info>(
+    memo_program: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    memo: &str,
+) -> Result<()> {
+    require!(memo.len() <= MAX_MEMO_LEN, WusdError::MemoTooLong);
+    invoke(
+        &spl_memo::build_memo(memo.as_bytes(), &[signer.key]),
+        &[signer.clone(), memo_program.clone()],
+    )?;
+    Ok(())
+}
+
+/// 加载钱包的KYC记录及其等级限额
+///
+/// KYC记录不存在时视为未验证等级，限额账户不存在时该等级不受限制。
+/// `tier_limits`须为记录所在等级的限额PDA，由客户端按记录中的等级传入。
+fn load_kyc(
+    mint: &Pubkey,
+    kyc_record: &AccountInfo,
+    tier_limits: &AccountInfo,
+    now: i64,
+) -> Result<(Option<KycRecord>, Option<KycTierLimits>)> {
+    let record = if kyc_record.data_is_empty() {
+        None
+    } else {
+        require_keys_eq!(*kyc_record.owner, crate::ID, WusdError::InvalidOwner);
+        let record = KycRecord::try_deserialize(&mut &kyc_record.try_borrow_data()?[..])?;
+        require!(!record.is_expired(now), WusdError::KycExpired);
+        Some(record)
+    };
+
+    let tier = record.as_ref().map_or(KycRecord::TIER_UNVERIFIED, |r| r.tier);
+    let (expected, _) =
+        Pubkey::find_program_address(&[b"kyc_tier", mint.as_ref(), &[tier]], &crate::ID);
+    require_keys_eq!(tier_limits.key(), expected, WusdError::InvalidKycTierLimits);
+    let limits = if tier_limits.data_is_empty() {
+        None
+    } else {
+        require_keys_eq!(*tier_limits.owner, crate::ID, WusdError::InvalidKycTierLimits);
+        Some(KycTierLimits::try_deserialize(&mut &tier_limits.try_borrow_data()?[..])?)
+    };
+
+    Ok((record, limits))
+}
+
+/// 校验发送方所在KYC等级的单笔及每日流出上限，并累计当日流出
+///
+/// 等级设置了每日流出上限时，流出累计账户不存在则由`payer`创建。
+/// 启用转账钩子的mint上，钩子另按全部Token-2022转账（含直接转账）累计并校验
+/// * `owner` - 发送方钱包地址
+/// * `outflow` - 发送方的流出累计PDA `[b"kyc_outflow", mint, owner]`
+/// * `outflow_bump` - 流出累计PDA的bump
+#[allow(clippy::too_many_arguments)]
+pub fn require_kyc_outflow<'info>(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    kyc_record: &AccountInfo<'info>,
+    tier_limits: &AccountInfo<'info>,
+    outflow: &AccountInfo<'info>,
+    outflow_bump: u8,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let (_, limits) = load_kyc(mint, kyc_record, tier_limits, now)?;
+    let Some(limits) = limits else {
+        return Ok(());
+    };
+
+    if let Some(max) = limits.max_single_transfer {
+        require!(amount <= max, WusdError::TransferLimitExceeded);
+    }
+    let Some(max) = limits.max_daily_outflow else {
+        return Ok(());
+    };
+
+    let mut state = if outflow.data_is_empty() {
+        create_pda_account(
+            payer,
+            outflow,
+            system_program,
+            &crate::ID,
+            KycOutflow::SIZE,
+            &[b"kyc_outflow", mint.as_ref(), owner.as_ref(), &[outflow_bump]],
+        )?;
+        KycOutflow {
+            mint: *mint,
+            account: *owner,
+            daily_outflow: DailyOutflow::default(),
+            bump: outflow_bump,
+        }
+    } else {
+        require_keys_eq!(*outflow.owner, crate::ID, WusdError::InvalidOwner);
+        KycOutflow::try_deserialize(&mut &outflow.try_borrow_data()?[..])?
+    };
+
+    state.daily_outflow.record(amount, max, now)?;
+    state.try_serialize(&mut &mut outflow.try_borrow_mut_data()?[..])?;
+
+    Ok(())
+}
+
+/// 校验接收方所在KYC等级的余额上限
+///
+/// 上限按单个代币账户计算，同一钱包的其他代币账户不计入
+/// * `balance_after` - 接收代币账户入账后的余额
+pub fn require_kyc_balance(
+    mint: &Pubkey,
+    kyc_record: &AccountInfo,
+    tier_limits: &AccountInfo,
+    balance_after: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let (_, limits) = load_kyc(mint, kyc_record, tier_limits, now)?;
+
+    if let Some(max) = limits.and_then(|l| l.max_balance) {
+        require!(balance_after <= max, WusdError::BalanceLimitExceeded);
+    }
+
+    Ok(())
+}
+
+/// 由程序创建PDA账户，账户地址须由`signer_seeds`派生
+///
+/// 与Anchor的`init`一致：账户已预先存入lamports时，改为补足租金后分配空间并指定owner，
+/// 避免他人向尚未创建的PDA转账导致`create_account`失败
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    space: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<()> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+    if current_lamports == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                account.key,
+                rent_exempt,
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), account.clone(), system_program.clone()],
+            &[signer_seeds],
+        )?;
+        return Ok(());
+    }
+
+    let required_lamports = rent_exempt.saturating_sub(current_lamports);
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(account.key, space as u64),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(account.key, program_id),
+        &[account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )?;
+    Ok(())
+}
+
+/// 关闭程序拥有的账户，租金退还给`destination`
+pub fn close_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.lamports.borrow_mut() = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(WusdError::InvalidAmount)?;
+    **account.lamports.borrow_mut() = 0;
+    account.assign(&System::id());
+    account.realloc(0, false)?;
+    Ok(())
+}
+
+/// 校验mint的永久委托是否为指定的authority
+///
+/// # 错误
+/// * `WusdError::PermanentDelegateNotConfigured` - mint未启用永久委托或委托不匹配
+pub fn require_permanent_delegate(mint: &AccountInfo, authority: &Pubkey) -> Result<()> {
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    require!(
+        get_permanent_delegate(&state) == Some(*authority),
+        WusdError::PermanentDelegateNotConfigured
+    );
+    Ok(())
+}
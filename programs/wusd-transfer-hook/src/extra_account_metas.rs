@@ -0,0 +1,190 @@
+//! ExtraAccountMetaList账户数据编码
+//!
+//! 布局与`spl-tlv-account-resolution`一致：
+//! Execute鉴别符(8) + 长度(4) + 元素个数(4) + ExtraAccountMeta(35) * n
+//!
+//! 当前锁定的依赖（spl-token-2022 0.6.1）不包含该crate，因此手动编码；
+//! 测试"Enforce the transfer hook on direct Token-2022 transfers"用@solana/spl-token解析本列表，
+//! 校验解析出的每个地址与`TransferHook`账户结构一致
+use anchor_lang::prelude::*;
+
+use crate::EXECUTE_DISCRIMINATOR;
+
+/// 单个ExtraAccountMeta的长度：鉴别符(1) + 地址配置(32) + is_signer(1) + is_writable(1)
+pub const EXTRA_ACCOUNT_META_LEN: usize = 35;
+
+/// Execute指令中固定账户的序号
+const SOURCE_INDEX: u8 = 0;
+const MINT_INDEX: u8 = 1;
+const DESTINATION_INDEX: u8 = 2;
+/// 额外账户中WUSD程序的序号（固定账户4个 + 校验账户1个）
+const WUSD_PROGRAM_INDEX: u8 = 5;
+
+/// 代币账户数据中owner字段的偏移和长度
+const TOKEN_ACCOUNT_OWNER_OFFSET: u8 = 32;
+const PUBKEY_LEN: u8 = 32;
+
+/// 额外账户地址的派生种子
+enum Seed<'a> {
+    /// 字面量
+    Literal(&'a [u8]),
+    /// 指定序号账户的地址
+    AccountKey { index: u8 },
+    /// 指定序号账户数据中的一段
+    AccountData { account_index: u8, data_index: u8, length: u8 },
+}
+
+impl Seed<'_> {
+    fn pack(&self, out: &mut Vec<u8>) {
+        match self {
+            Seed::Literal(bytes) => {
+                out.extend_from_slice(&[1, bytes.len() as u8]);
+                out.extend_from_slice(bytes);
+            }
+            Seed::AccountKey { index } => out.extend_from_slice(&[3, *index]),
+            Seed::AccountData { account_index, data_index, length } => {
+                out.extend_from_slice(&[4, *account_index, *data_index, *length])
+            }
+        }
+    }
+}
+
+/// 固定地址的额外账户
+fn fixed(address: &Pubkey) -> [u8; EXTRA_ACCOUNT_META_LEN] {
+    let mut meta = [0u8; EXTRA_ACCOUNT_META_LEN];
+    meta[1..33].copy_from_slice(address.as_ref());
+    meta
+}
+
+/// 按种子派生的PDA，鉴别符为1时由钩子程序派生，为128 + i时由序号i的账户作为程序ID派生
+fn derived(discriminator: u8, seeds: &[Seed]) -> [u8; EXTRA_ACCOUNT_META_LEN] {
+    let mut config = Vec::with_capacity(32);
+    for seed in seeds {
+        seed.pack(&mut config);
+    }
+    let mut meta = [0u8; EXTRA_ACCOUNT_META_LEN];
+    meta[0] = discriminator;
+    meta[1..1 + config.len()].copy_from_slice(&config);
+    meta
+}
+
+/// 由钩子程序自身派生的PDA
+fn pda(seeds: &[Seed]) -> [u8; EXTRA_ACCOUNT_META_LEN] {
+    derived(1, seeds)
+}
+
+/// 由指定序号账户作为程序ID派生的外部PDA
+fn external_pda(program_index: u8, seeds: &[Seed]) -> [u8; EXTRA_ACCOUNT_META_LEN] {
+    derived(128 + program_index, seeds)
+}
+
+/// 将额外账户标记为可写
+fn writable(mut meta: [u8; EXTRA_ACCOUNT_META_LEN]) -> [u8; EXTRA_ACCOUNT_META_LEN] {
+    meta[EXTRA_ACCOUNT_META_LEN - 1] = 1;
+    meta
+}
+
+/// Execute所需的额外账户，顺序需与`TransferHook`账户结构一致
+fn extra_account_metas() -> [[u8; EXTRA_ACCOUNT_META_LEN]; 14] {
+    let owner_of = |account_index| Seed::AccountData {
+        account_index,
+        data_index: TOKEN_ACCOUNT_OWNER_OFFSET,
+        length: PUBKEY_LEN,
+    };
+    let tier_limits = |tier: u8| {
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"kyc_tier"),
+                Seed::AccountKey { index: MINT_INDEX },
+                Seed::Literal(&[tier]),
+            ],
+        )
+    };
+    [
+        fixed(&wusd_token::ID),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[Seed::Literal(b"pause_state"), Seed::AccountKey { index: MINT_INDEX }],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[Seed::Literal(b"freeze"), Seed::AccountKey { index: SOURCE_INDEX }],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[Seed::Literal(b"freeze"), Seed::AccountKey { index: DESTINATION_INDEX }],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"blacklist"),
+                Seed::AccountKey { index: MINT_INDEX },
+                owner_of(SOURCE_INDEX),
+            ],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"blacklist"),
+                Seed::AccountKey { index: MINT_INDEX },
+                owner_of(DESTINATION_INDEX),
+            ],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[Seed::Literal(b"access_registry"), Seed::AccountKey { index: MINT_INDEX }],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"allowlist"),
+                Seed::AccountKey { index: MINT_INDEX },
+                owner_of(DESTINATION_INDEX),
+            ],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"kyc"),
+                Seed::AccountKey { index: MINT_INDEX },
+                owner_of(SOURCE_INDEX),
+            ],
+        ),
+        external_pda(
+            WUSD_PROGRAM_INDEX,
+            &[
+                Seed::Literal(b"kyc"),
+                Seed::AccountKey { index: MINT_INDEX },
+                owner_of(DESTINATION_INDEX),
+            ],
+        ),
+        tier_limits(0),
+        tier_limits(1),
+        tier_limits(2),
+        writable(pda(&[
+            Seed::Literal(b"kyc_outflow"),
+            Seed::AccountKey { index: MINT_INDEX },
+            owner_of(SOURCE_INDEX),
+        ])),
+    ]
+}
+
+/// ExtraAccountMetaList账户大小
+pub fn account_size() -> usize {
+    8 + 4 + 4 + EXTRA_ACCOUNT_META_LEN * extra_account_metas().len()
+}
+
+/// 将额外账户列表写入ExtraAccountMetaList账户
+pub fn write(data: &mut [u8]) {
+    let metas = extra_account_metas();
+    let value_len = (4 + EXTRA_ACCOUNT_META_LEN * metas.len()) as u32;
+
+    data[..8].copy_from_slice(&EXECUTE_DISCRIMINATOR);
+    data[8..12].copy_from_slice(&value_len.to_le_bytes());
+    data[12..16].copy_from_slice(&(metas.len() as u32).to_le_bytes());
+    for (i, meta) in metas.iter().enumerate() {
+        let start = 16 + i * EXTRA_ACCOUNT_META_LEN;
+        data[start..start + EXTRA_ACCOUNT_META_LEN].copy_from_slice(meta);
+    }
+}
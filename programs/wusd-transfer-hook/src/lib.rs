@@ -0,0 +1,400 @@
+//! WUSD 转账钩子程序
+//!
+//! 实现`spl-transfer-hook-interface`的Execute指令，
+//! 无论转账通过WUSD程序还是直接调用Token-2022提交，都会校验暂停、冻结、黑名单、白名单状态及KYC等级限额
+#![allow(clippy::result_large_err)]
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use wusd_token::error::WusdError;
+use wusd_token::program::WusdToken;
+use wusd_token::state::{
+    AccessRegistryState, DailyOutflow, FreezeState, KycRecord, KycTierLimits, PauseState,
+};
+
+mod extra_account_metas;
+
+declare_id!("D6xjtPxq19pPKCVBuw6s61JDUa3sULeFFPay1EJhsk43");
+
+/// Execute指令鉴别符，sha256("spl-transfer-hook-interface:execute")[..8]
+pub const EXECUTE_DISCRIMINATOR: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+
+#[program]
+pub mod wusd_transfer_hook {
+    use super::*;
+
+    /// 初始化mint的ExtraAccountMetaList账户，列表内容固定，任何人均可创建
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        let mut data = ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?;
+        extra_account_metas::write(&mut data);
+        Ok(())
+    }
+
+    /// 将已创建的ExtraAccountMetaList改写为当前列表，列表增长所需的租金由`payer`补足
+    pub fn update_extra_account_meta_list(
+        ctx: Context<UpdateExtraAccountMetaList>,
+    ) -> Result<()> {
+        let list = ctx.accounts.extra_account_meta_list.to_account_info();
+        list.realloc(extra_account_metas::account_size(), false)?;
+        let required = Rent::get()?.minimum_balance(list.data_len());
+        if required > list.lamports() {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: list.clone(),
+                    },
+                ),
+                required - list.lamports(),
+            )?;
+        }
+        extra_account_metas::write(&mut list.try_borrow_mut_data()?);
+        Ok(())
+    }
+
+    /// 为钱包创建转账钩子的每日流出累计账户，任何人均可代为创建
+    ///
+    /// 钩子执行时无法支付租金，钱包所在KYC等级设置了每日流出上限时须先创建该账户
+    pub fn initialize_transfer_outflow(
+        ctx: Context<InitializeTransferOutflow>,
+        wallet: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.transfer_outflow.set_inner(TransferOutflow {
+            mint: ctx.accounts.mint.key(),
+            account: wallet,
+            daily_outflow: DailyOutflow::default(),
+            bump: *ctx.bumps.get("transfer_outflow").unwrap(),
+        });
+        Ok(())
+    }
+
+    /// 转账钩子，由Token-2022在每次转账时调用
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        // WUSD程序以authority_state PDA身份发起的转账（清除冻结账户、赎回退款）不受限制
+        let mint_key = ctx.accounts.mint.key();
+        let (authority_state, _) =
+            Pubkey::find_program_address(&[b"authority", mint_key.as_ref()], &wusd_token::ID);
+        if ctx.accounts.owner.key() == authority_state {
+            return Ok(());
+        }
+
+        ctx.accounts.pause_state.validate_not_paused()?;
+        require_not_frozen(&ctx.accounts.source_freeze_state)?;
+        require_not_frozen(&ctx.accounts.destination_freeze_state)?;
+        require!(
+            ctx.accounts.source_blacklist.data_is_empty(),
+            WusdError::Blacklisted
+        );
+        require!(
+            ctx.accounts.destination_blacklist.data_is_empty(),
+            WusdError::Blacklisted
+        );
+        // 转入authority_state名下账户（如赎回金库）不受白名单和余额上限限制
+        if ctx.accounts.destination_token.owner != authority_state {
+            require_allowlisted(
+                &ctx.accounts.access_registry,
+                &ctx.accounts.destination_allowlist,
+            )?;
+        }
+
+        // 发送方所在KYC等级的单笔及每日流出上限
+        let now = Clock::get()?.unix_timestamp;
+        let tier_limits: [&AccountInfo; KycRecord::TIER_COUNT as usize] = [
+            &ctx.accounts.tier_0_limits,
+            &ctx.accounts.tier_1_limits,
+            &ctx.accounts.tier_2_limits,
+        ];
+        if let Some(limits) = load_tier_limits(&ctx.accounts.source_kyc, &tier_limits, now)? {
+            if let Some(max) = limits.max_single_transfer {
+                require!(amount <= max, WusdError::TransferLimitExceeded);
+            }
+            if let Some(max) = limits.max_daily_outflow {
+                let outflow = &ctx.accounts.source_outflow;
+                require!(!outflow.data_is_empty(), WusdError::KycOutflowNotInitialized);
+                let mut state = Account::<TransferOutflow>::try_from(outflow)?;
+                state.daily_outflow.record(amount, max, now)?;
+                state.exit(&crate::ID)?;
+            }
+        }
+
+        // 接收方所在KYC等级的余额上限，钩子在Token-2022完成记账后执行，余额已包含本次转入
+        if ctx.accounts.destination_token.owner != authority_state {
+            let limits = load_tier_limits(&ctx.accounts.destination_kyc, &tier_limits, now)?;
+            if let Some(max) = limits.and_then(|l| l.max_balance) {
+                require!(
+                    ctx.accounts.destination_token.amount <= max,
+                    WusdError::BalanceLimitExceeded
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 将接口的Execute指令转发给`transfer_hook`
+    pub fn fallback(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> Result<()> {
+        require!(data.len() >= 16, ErrorCode::InstructionFallbackNotFound);
+        let (discriminator, amount) = data.split_at(8);
+        require!(
+            discriminator == EXECUTE_DISCRIMINATOR,
+            ErrorCode::InstructionFallbackNotFound
+        );
+        __private::__global::transfer_hook(program_id, accounts, &amount[..8])
+    }
+}
+
+/// 冻结状态账户不存在时视为未冻结
+fn require_not_frozen(freeze_state: &AccountInfo) -> Result<()> {
+    if freeze_state.data_is_empty() {
+        return Ok(());
+    }
+    Account::<FreezeState>::try_from(freeze_state)?.check_frozen()
+}
+
+/// 白名单模式下目标账户owner须在白名单中，访问注册表不存在时视为未启用
+fn require_allowlisted(access_registry: &AccountInfo, allowlist_entry: &AccountInfo) -> Result<()> {
+    if access_registry.data_is_empty() {
+        return Ok(());
+    }
+    let registry = Account::<AccessRegistryState>::try_from(access_registry)?;
+    require!(
+        !registry.allowlist_enabled || !allowlist_entry.data_is_empty(),
+        WusdError::RecipientNotAllowlisted
+    );
+    Ok(())
+}
+
+/// 加载钱包所在KYC等级的限额
+///
+/// KYC记录不存在时视为未验证等级，限额账户不存在时该等级不受限制
+fn load_tier_limits(
+    kyc_record: &AccountInfo,
+    tier_limits: &[&AccountInfo],
+    now: i64,
+) -> Result<Option<KycTierLimits>> {
+    let tier = if kyc_record.data_is_empty() {
+        KycRecord::TIER_UNVERIFIED
+    } else {
+        let record = Account::<KycRecord>::try_from(kyc_record)?;
+        require!(!record.is_expired(now), WusdError::KycExpired);
+        record.tier
+    };
+    let limits = tier_limits
+        .get(tier as usize)
+        .ok_or(WusdError::InvalidKycTierLimits)?;
+    if limits.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(Account::<KycTierLimits>::try_from(limits)?.into_inner()))
+}
+
+/// 钱包经由Token-2022转账（含WUSD程序发起的转账）的每日流出累计，由转账钩子维护
+///
+/// 与WUSD程序的`KycOutflow`相互独立：钩子无法修改WUSD程序的账户，
+/// 也不能在WUSD程序发起的转账中回调WUSD程序
+#[account]
+pub struct TransferOutflow {
+    /// 所属的代币铸币账户
+    pub mint: Pubkey,
+    /// 钱包地址
+    pub account: Pubkey,
+    /// 当日流出累计
+    pub daily_outflow: DailyOutflow,
+    /// PDA bump
+    pub bump: u8,
+}
+
+impl TransferOutflow {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // mint
+        32 + // account
+        DailyOutflow::SIZE + // daily_outflow
+        1; // bump
+}
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: 按spl-tlv-account-resolution布局手动写入
+    #[account(
+        init,
+        payer = payer,
+        space = extra_account_metas::account_size(),
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: 按spl-tlv-account-resolution布局手动改写
+    #[account(
+        mut,
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct InitializeTransferOutflow<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = TransferOutflow::SIZE,
+        seeds = [b"kyc_outflow", mint.key().as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub transfer_outflow: Account<'info, TransferOutflow>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Execute指令账户，前5个由接口固定，其余顺序与ExtraAccountMetaList一致
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(constraint = source_token.mint == mint.key() @ WusdError::InvalidMint)]
+    pub source_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = destination_token.mint == mint.key() @ WusdError::InvalidMint)]
+    pub destination_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: 源账户的owner或委托，签名由Token-2022校验
+    pub owner: AccountInfo<'info>,
+
+    /// CHECK: ExtraAccountMetaList账户，地址由种子校验
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()],
+        bump
+    )]
+    pub extra_account_meta_list: AccountInfo<'info>,
+
+    pub wusd_program: Program<'info, WusdToken>,
+
+    #[account(
+        seeds = [b"pause_state", mint.key().as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub pause_state: Account<'info, PauseState>,
+
+    /// CHECK: 源账户的冻结状态，可能尚未创建
+    #[account(
+        seeds = [b"freeze", source_token.key().as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub source_freeze_state: AccountInfo<'info>,
+
+    /// CHECK: 目标账户的冻结状态，可能尚未创建
+    #[account(
+        seeds = [b"freeze", destination_token.key().as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub destination_freeze_state: AccountInfo<'info>,
+
+    /// CHECK: 源账户owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub source_blacklist: AccountInfo<'info>,
+
+    /// CHECK: 目标账户owner的黑名单条目PDA，账户不存在表示未被列入黑名单
+    #[account(
+        seeds = [b"blacklist", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub destination_blacklist: AccountInfo<'info>,
+
+    /// CHECK: mint的访问注册表，可能尚未创建
+    #[account(
+        seeds = [b"access_registry", mint.key().as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub access_registry: AccountInfo<'info>,
+
+    /// CHECK: 目标账户owner的白名单条目PDA，仅白名单模式下要求账户存在
+    #[account(
+        seeds = [b"allowlist", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub destination_allowlist: AccountInfo<'info>,
+
+    /// CHECK: 源账户owner的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub source_kyc: AccountInfo<'info>,
+
+    /// CHECK: 目标账户owner的KYC记录PDA，账户不存在表示未验证等级
+    #[account(
+        seeds = [b"kyc", mint.key().as_ref(), destination_token.owner.as_ref()],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub destination_kyc: AccountInfo<'info>,
+
+    /// CHECK: 等级0的限额PDA，账户不存在表示该等级不受限制
+    #[account(
+        seeds = [b"kyc_tier", mint.key().as_ref(), &[0]],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub tier_0_limits: AccountInfo<'info>,
+
+    /// CHECK: 等级1的限额PDA，账户不存在表示该等级不受限制
+    #[account(
+        seeds = [b"kyc_tier", mint.key().as_ref(), &[1]],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub tier_1_limits: AccountInfo<'info>,
+
+    /// CHECK: 等级2的限额PDA，账户不存在表示该等级不受限制
+    #[account(
+        seeds = [b"kyc_tier", mint.key().as_ref(), &[2]],
+        bump,
+        seeds::program = wusd_program.key()
+    )]
+    pub tier_2_limits: AccountInfo<'info>,
+
+    /// CHECK: 源账户owner的每日流出累计，仅在所在等级设置了每日流出上限时要求账户存在
+    #[account(
+        mut,
+        seeds = [b"kyc_outflow", mint.key().as_ref(), source_token.owner.as_ref()],
+        bump
+    )]
+    pub source_outflow: AccountInfo<'info>,
+}
@@ -1,22 +1,40 @@
 import * as nacl from "tweetnacl";
+import { createHash } from "crypto";
 import {
   LAMPORTS_PER_SOL,
   SystemProgram,
   PublicKey,
   Keypair,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
 } from "@solana/web3.js";
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import {
   TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
   createInitializeMint2Instruction,
+  createMintToInstruction,
   createAssociatedTokenAccountInstruction,
   getAssociatedTokenAddressSync,
-  createApproveInstruction,
+  createTransferCheckedWithTransferHookInstruction,
+  getAccount,
+  getExtraAccountMetaAddress,
+  getMetadataPointerState,
+  getMint,
+  getPermanentDelegate,
+  getTokenMetadata,
+  getTransferFeeAmount,
+  getTransferHook,
 } from "@solana/spl-token";
 import { WusdToken } from "../target/types/wusd_token";
+import { WusdTransferHook } from "../target/types/wusd_transfer_hook";
 import { assert } from "chai";
 
+const MEMO_PROGRAM_ID = new PublicKey(
+  "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+);
+
 describe("WUSD Token Test", () => {
   // 1. 首先定义所有变量
   const provider = anchor.AnchorProvider.env();
@@ -53,6 +71,486 @@ describe("WUSD Token Test", () => {
   const sleep = (ms: number) =>
     new Promise((resolve) => setTimeout(resolve, ms));
 
+  // 角色及其在角色账户PDA种子中的序号
+  const ROLES = {
+    minter: { name: { minter: {} }, seed: 0 },
+    burner: { name: { burner: {} }, seed: 1 },
+    pauser: { name: { pauser: {} }, seed: 2 },
+    freezer: { name: { freezer: {} }, seed: 3 },
+    operatorAdmin: { name: { operatorAdmin: {} }, seed: 4 },
+    feeManager: { name: { feeManager: {} }, seed: 5 },
+    rescuer: { name: { rescuer: {} }, seed: 6 },
+    masterMinter: { name: { masterMinter: {} }, seed: 7 },
+    wiper: { name: { wiper: {} }, seed: 8 },
+    blacklister: { name: { blacklister: {} }, seed: 9 },
+    allowlister: { name: { allowlister: {} }, seed: 10 },
+    compliance: { name: { compliance: {} }, seed: 11 },
+  };
+  type RoleDef = (typeof ROLES)[keyof typeof ROLES];
+
+  const rolePda = (role: RoleDef) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("role"),
+        mintKeypair.publicKey.toBuffer(),
+        Buffer.from([role.seed]),
+      ],
+      programId
+    )[0];
+
+  const minterPda = (minter: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("minter"),
+        mintKeypair.publicKey.toBuffer(),
+        minter.toBuffer(),
+      ],
+      programId
+    )[0];
+
+  const minterRateLimitPda = (minter: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("minter_rate_limit"),
+        mintKeypair.publicKey.toBuffer(),
+        minter.toBuffer(),
+      ],
+      programId
+    )[0];
+
+  // 授权额度PDA，按mint隔离
+  const allowancePda = (
+    owner: PublicKey,
+    spender: PublicKey,
+    mint: PublicKey = mintKeypair.publicKey
+  ) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("allowance"), mint.toBuffer(), owner.toBuffer(), spender.toBuffer()],
+      programId
+    )[0];
+
+  // 与程序中 Permission 一致的操作员权限位
+  const PERMISSIONS = {
+    transfer: 1 << 0,
+    transferFrom: 1 << 1,
+    burn: 1 << 2,
+    mintTo: 1 << 3,
+    freeze: 1 << 4,
+    relayPermit: 1 << 5,
+    all: (1 << 6) - 1,
+  };
+
+  const blacklistPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("blacklist"),
+        mintKeypair.publicKey.toBuffer(),
+        account.toBuffer(),
+      ],
+      programId
+    )[0];
+
+  const allowlistPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("allowlist"),
+        mintKeypair.publicKey.toBuffer(),
+        account.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  const kycPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc"), mintKeypair.publicKey.toBuffer(), account.toBuffer()],
+      programId
+    )[0];
+
+  const kycOutflowPda = (account: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc_outflow"), mintKeypair.publicKey.toBuffer(), account.toBuffer()],
+      programId
+    )[0];
+
+  // 无KYC记录的钱包按未验证等级（0）传入限额账户
+  const kycTierPda = (tier = 0) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("kyc_tier"), mintKeypair.publicKey.toBuffer(), Buffer.from([tier])],
+      programId
+    )[0];
+
+  const operatorPda = (operator: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("operator"),
+        mintKeypair.publicKey.toBuffer(),
+        operator.toBuffer(),
+      ],
+      program.programId
+    )[0];
+
+  // 添加永不过期的操作员，已存在时跳过
+  const addOperator = async (
+    operator: PublicKey,
+    label: string,
+    permissions = PERMISSIONS.all
+  ) => {
+    if (await provider.connection.getAccountInfo(operatorPda(operator))) {
+      return;
+    }
+    const tx = await program.methods
+      .addOperator(operator, label, new anchor.BN(0), permissions)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        operatorAdminRole: rolePda(ROLES.operatorAdmin),
+        accessRegistry: accessRegistryPda,
+        operatorRecord: operatorPda(operator),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await provider.connection.confirmTransaction(tx, "confirmed");
+  };
+
+  // 由钱包作为铸币者向接收者铸币，`tier`为接收者当前的KYC等级
+  const mintTokens = (amount: anchor.BN, tier = 0) =>
+    program.methods
+      .mint(amount, authorityBump, null)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mintKeypair.publicKey,
+        tokenAccount: recipientTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        authorityState: authorityPda,
+        mintState: mintStatePda,
+        pauseState: pauseStatePda,
+        accessRegistry: accessRegistryPda,
+        minterState: minterPda(provider.wallet.publicKey),
+        minterRole: rolePda(ROLES.minter),
+        minterRateLimit: minterRateLimitPda(provider.wallet.publicKey),
+        recipientBlacklist: blacklistPda(recipientKeypair.publicKey),
+        recipientAllowlist: allowlistPda(recipientKeypair.publicKey),
+        recipientKyc: kycPda(recipientKeypair.publicKey),
+        recipientKycLimits: kycTierPda(tier),
+      })
+      .rpc();
+
+  // 由管理员（钱包）授予角色
+  const grantRole = async (role: RoleDef, member: PublicKey) => {
+    const tx = await program.methods
+      .grantRole(role.name, member)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        authorityState: authorityPda,
+        tokenMint: mintKeypair.publicKey,
+        roleState: rolePda(role),
+        adminRoleState: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await provider.connection.confirmTransaction(tx, "confirmed");
+  };
+
+  // 构造与程序中 PermitMessage 一致的消息并由owner签名
+  const signPermit = (
+    owner: Keypair,
+    spender: PublicKey,
+    amount: anchor.BN,
+    deadline: number,
+    nonce: anchor.BN | null,
+    scope: object
+  ) => {
+    const version = Buffer.alloc(32);
+    version.write("1");
+    const domainSeparator = createHash("sha256")
+      .update(Buffer.from("WUSD Permit"))
+      .update(programId.toBuffer())
+      .update(mintKeypair.publicKey.toBuffer())
+      .update(Buffer.from("1"))
+      .digest();
+    const message = program.coder.types.encode("PermitMessage", {
+      contract: programId,
+      domainSeparator: Array.from(domainSeparator),
+      owner: owner.publicKey,
+      spender,
+      amount,
+      nonce: nonce ?? new anchor.BN(0),
+      deadline: new anchor.BN(deadline),
+      scope,
+      chainId: new anchor.BN(0),
+      version: Array.from(version),
+    });
+    const signature = nacl.sign.detached(message, owner.secretKey);
+    return { message, signature };
+  };
+
+  // 提交owner签名的permit，默认由钱包作为中继者
+  const sendPermit = async (
+    owner: Keypair,
+    spender: PublicKey,
+    amount: anchor.BN,
+    deadline: number,
+    nonce: anchor.BN | null,
+    scope: object,
+    relayer: Keypair | null = null
+  ) => {
+    const relayerKey = relayer?.publicKey ?? provider.wallet.publicKey;
+    const { message, signature } = signPermit(
+      owner,
+      spender,
+      amount,
+      deadline,
+      nonce,
+      scope
+    );
+    const ed25519Ix = Ed25519Program.createInstructionWithPublicKey({
+      publicKey: owner.publicKey.toBytes(),
+      message,
+      signature,
+    });
+    return program.methods
+      .permit({
+        amount,
+        deadline: new anchor.BN(deadline),
+        nonce,
+        scope,
+        signature: Array.from(signature),
+        publicKey: Array.from(owner.publicKey.toBytes()),
+      })
+      .accounts({
+        relayer: relayerKey,
+        owner: owner.publicKey,
+        spender,
+        allowance: allowancePda(owner.publicKey, spender),
+        mintState: mintStatePda,
+        ownerBlacklist: blacklistPda(owner.publicKey),
+        spenderBlacklist: blacklistPda(spender),
+        accessRegistry: accessRegistryPda,
+        operatorRecord: (await provider.connection.getAccountInfo(
+          operatorPda(relayerKey)
+        ))
+          ? operatorPda(relayerKey)
+          : null,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      })
+      .preInstructions([ed25519Ix])
+      .signers(relayer ? [relayer] : [])
+      .rpc();
+  };
+
+  // 通过initialize创建带扩展（元数据、手续费、转账钩子、永久委托）的新mint
+  const initializeMint = async (
+    transferFeeBasisPoints = 0,
+    maximumFee = new anchor.BN(0)
+  ) => {
+    const mint = Keypair.generate();
+    const pda = (seed: string) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(seed), mint.publicKey.toBuffer()],
+        program.programId
+      )[0];
+    const authority = pda("authority");
+    const mintState = pda("mint_state");
+    const pauseState = pda("pause_state");
+
+    await program.methods
+      .initialize(
+        6,
+        "Wrapped USD",
+        "WUSD",
+        "https://example.com/wusd.json",
+        transferFeeBasisPoints,
+        maximumFee
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        authorityState: authority,
+        tokenMint: mint.publicKey,
+        mintState,
+        pauseState,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .signers([mint])
+      .rpc();
+
+    return { mint, authority, mintState, pauseState, pda };
+  };
+
+  // 在initialize创建的mint上初始化访问注册表和钩子额外账户列表，配置钱包为铸币者，
+  // 并向新钱包holder铸币，供直接调用Token-2022转账的测试使用
+  const initializeHookMint = async (
+    transferFeeBasisPoints = 0,
+    maximumFee = new anchor.BN(0),
+    amount = 10000000
+  ) => {
+    const hookMint = await initializeMint(transferFeeBasisPoints, maximumFee);
+    const { mint, authority, mintState, pauseState, pda } = hookMint;
+    const hookProgram = anchor.workspace
+      .WusdTransferHook as Program<WusdTransferHook>;
+    const accessRegistry = pda("access_registry");
+    const walletPda = (seed: string, wallet: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(seed), mint.publicKey.toBuffer(), wallet.toBuffer()],
+        program.programId
+      )[0];
+    const roleOf = (role: RoleDef) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("role"), mint.publicKey.toBuffer(), Buffer.from([role.seed])],
+        program.programId
+      )[0];
+    const tierOf = (tier = 0) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("kyc_tier"), mint.publicKey.toBuffer(), Buffer.from([tier])],
+        program.programId
+      )[0];
+    const ata = (owner: PublicKey) =>
+      getAssociatedTokenAddressSync(
+        mint.publicKey,
+        owner,
+        true,
+        TOKEN_2022_PROGRAM_ID
+      );
+    const grant = (role: RoleDef, member: PublicKey) =>
+      program.methods
+        .grantRole(role.name, member)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          authorityState: authority,
+          tokenMint: mint.publicKey,
+          roleState: roleOf(role),
+          adminRoleState: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    await program.methods
+      .initializeAccessRegistry()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mint.publicKey,
+        authorityState: authority,
+        accessRegistry,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        mint: mint.publicKey,
+        extraAccountMetaList: getExtraAccountMetaAddress(
+          mint.publicKey,
+          hookProgram.programId
+        ),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await grant(ROLES.masterMinter, provider.wallet.publicKey);
+    await program.methods
+      .configureMinter(provider.wallet.publicKey, new anchor.BN(amount))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mint.publicKey,
+        masterMinterRole: roleOf(ROLES.masterMinter),
+        minterState: walletPda("minter", provider.wallet.publicKey),
+        minterRole: roleOf(ROLES.minter),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const holder = Keypair.generate();
+    const receiver = Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        ...[holder, receiver].map((owner) =>
+          createAssociatedTokenAccountInstruction(
+            provider.wallet.publicKey,
+            ata(owner.publicKey),
+            owner.publicKey,
+            mint.publicKey,
+            TOKEN_2022_PROGRAM_ID
+          )
+        )
+      )
+    );
+    const [, bump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), mint.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .mint(new anchor.BN(amount), bump, null)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mint.publicKey,
+        tokenAccount: ata(holder.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        authorityState: authority,
+        mintState,
+        pauseState,
+        accessRegistry,
+        minterState: walletPda("minter", provider.wallet.publicKey),
+        minterRole: roleOf(ROLES.minter),
+        minterRateLimit: walletPda(
+          "minter_rate_limit",
+          provider.wallet.publicKey
+        ),
+        recipientBlacklist: walletPda("blacklist", holder.publicKey),
+        recipientAllowlist: walletPda("allowlist", holder.publicKey),
+        recipientKyc: walletPda("kyc", holder.publicKey),
+        recipientKycLimits: tierOf(0),
+      })
+      .rpc();
+
+    // 不经WUSD程序，直接提交Token-2022转账，钩子所需的额外账户由客户端按列表解析
+    const transfer = async (from: Keypair, to: PublicKey, amount: number) =>
+      provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          await createTransferCheckedWithTransferHookInstruction(
+            provider.connection,
+            ata(from.publicKey),
+            mint.publicKey,
+            ata(to),
+            from.publicKey,
+            amount,
+            6,
+            [],
+            "confirmed",
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [from]
+      );
+
+    return {
+      ...hookMint,
+      hookProgram,
+      accessRegistry,
+      walletPda,
+      roleOf,
+      tierOf,
+      ata,
+      grant,
+      holder,
+      receiver,
+      transfer,
+    };
+  };
+
+  // 直接提交的交易失败时，CPI中钩子返回的错误名称只出现在程序日志中
+  const expectFailure = async (promise: Promise<unknown>, expected: string) => {
+    try {
+      await promise;
+    } catch (error) {
+      assert.include(`${error}\n${(error.logs ?? []).join("\n")}`, expected);
+      return;
+    }
+    assert.fail(`expected the transaction to fail with ${expected}`);
+  };
+
   before(async () => {
     try {
       console.log("Starting initialization with simplified approach...");
@@ -96,7 +594,7 @@ describe("WUSD Token Test", () => {
       );
 
       [accessRegistryPda] = PublicKey.findProgramAddressSync(
-        [Buffer.from("access_registry")],
+        [Buffer.from("access_registry"), mintKeypair.publicKey.toBuffer()],
         programId
       );
 
@@ -165,7 +663,7 @@ describe("WUSD Token Test", () => {
             mintKeypair.publicKey,
             6, // 6位小数
             provider.wallet.publicKey, // 先使用钱包作为铸币权限，后续再转移给PDA
-            null,
+            provider.wallet.publicKey, // 冻结权限同样在初始化时转移给PDA
             TOKEN_2022_PROGRAM_ID
           );
 
@@ -346,6 +844,8 @@ describe("WUSD Token Test", () => {
         .initializeAccessRegistry()
         .accounts({
           authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
           accessRegistry: accessRegistryPda,
           systemProgram: SystemProgram.programId,
         })
@@ -367,6 +867,7 @@ describe("WUSD Token Test", () => {
       if (!accessRegistry.initialized) {
         throw new Error("Access Registry initialization failed");
       }
+      assert.ok(accessRegistry.mint.equals(mintKeypair.publicKey));
 
       console.log("Access Registry initialized successfully");
     } catch (error) {
@@ -401,18 +902,34 @@ describe("WUSD Token Test", () => {
         throw new Error("Access Registry not initialized after retries");
       }
 
-      // 添加铸币权限
-      const tx = await program.methods
-        .addOperator(provider.wallet.publicKey)
+      // 授予钱包主铸币者和操作员管理角色
+      await grantRole(ROLES.masterMinter, provider.wallet.publicKey);
+      await grantRole(ROLES.operatorAdmin, provider.wallet.publicKey);
+
+      // 将钱包配置为铸币者
+      const configureMinterTx = await program.methods
+        .configureMinter(provider.wallet.publicKey, new anchor.BN(20000000000))
         .accounts({
           authority: provider.wallet.publicKey,
-          authorityState: authorityPda,
-          accessRegistry: accessRegistryPda,
-          operator: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          masterMinterRole: rolePda(ROLES.masterMinter),
+          minterState: minterPda(provider.wallet.publicKey),
+          minterRole: rolePda(ROLES.minter),
+          systemProgram: SystemProgram.programId,
         })
         .rpc();
+      await provider.connection.confirmTransaction(configureMinterTx);
+
+      // 配置铸币者时同步授予Minter角色
+      const minterRole = await program.account.roleState.fetch(
+        rolePda(ROLES.minter)
+      );
+      assert.isTrue(
+        minterRole.members
+          .slice(0, minterRole.memberCount)
+          .some((m) => m.equals(provider.wallet.publicKey))
+      );
 
-      await provider.connection.confirmTransaction(tx);
       console.log("Minter access granted");
     } catch (error) {
       console.error("Failed to set minter access:", error);
@@ -420,6 +937,228 @@ describe("WUSD Token Test", () => {
     }
   });
 
+  it("Grant, revoke and renounce roles", async () => {
+    const member = Keypair.generate();
+    const pauserRole = rolePda(ROLES.pauser);
+
+    await grantRole(ROLES.pauser, member.publicKey);
+    let roleState = await program.account.roleState.fetch(pauserRole);
+    assert.isTrue(
+      roleState.members
+        .slice(0, roleState.memberCount)
+        .some((m) => m.equals(member.publicKey))
+    );
+
+    // 成员主动放弃角色
+    await program.methods
+      .renounceRole(ROLES.pauser.name)
+      .accounts({
+        member: member.publicKey,
+        roleState: pauserRole,
+      })
+      .signers([member])
+      .rpc();
+    roleState = await program.account.roleState.fetch(pauserRole);
+    assert.isFalse(
+      roleState.members
+        .slice(0, roleState.memberCount)
+        .some((m) => m.equals(member.publicKey))
+    );
+
+    // 非管理员不能授予角色
+    try {
+      await program.methods
+        .grantRole(ROLES.pauser.name, member.publicKey)
+        .accounts({
+          authority: member.publicKey,
+          authorityState: authorityPda,
+          tokenMint: mintKeypair.publicKey,
+          roleState: pauserRole,
+          adminRoleState: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+      assert.fail("Non-admin should not grant roles");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+  });
+
+  it("Pause and unpause as admin without the Pauser role", async () => {
+    const pauseAccounts = {
+      pauseState: pauseStatePda,
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      authorityState: authorityPda,
+      pauserRole: null,
+    };
+
+    await program.methods.pause().accounts(pauseAccounts).rpc();
+    let pauseState = await program.account.pauseState.fetch(pauseStatePda);
+    assert.isTrue(pauseState.paused);
+
+    await program.methods.unpause().accounts(pauseAccounts).rpc();
+    pauseState = await program.account.pauseState.fetch(pauseStatePda);
+    assert.isFalse(pauseState.paused);
+  });
+
+  it("Two-step admin transfer", async () => {
+    const newAdmin = Keypair.generate();
+    const adminAccounts = {
+      authorityState: authorityPda,
+      tokenMint: mintKeypair.publicKey,
+    };
+
+    // 提议后可以取消
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ authority: provider.wallet.publicKey, ...adminAccounts })
+      .rpc();
+    await program.methods
+      .cancelAdminTransfer()
+      .accounts({ authority: provider.wallet.publicKey, ...adminAccounts })
+      .rpc();
+    let authorityState = await program.account.authorityState.fetch(
+      authorityPda
+    );
+    assert.isNull(authorityState.pendingAdmin);
+
+    // 提议并由新管理员接受
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ authority: provider.wallet.publicKey, ...adminAccounts })
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: newAdmin.publicKey,
+        accessRegistry: accessRegistryPda,
+        ...adminAccounts,
+      })
+      .signers([newAdmin])
+      .rpc();
+    authorityState = await program.account.authorityState.fetch(authorityPda);
+    assert.isTrue(authorityState.admin.equals(newAdmin.publicKey));
+
+    // 访问注册表的管理员随之转移，原管理员不再拥有全部权限
+    let registry = await program.account.accessRegistryState.fetch(
+      accessRegistryPda
+    );
+    assert.isTrue(registry.authority.equals(newAdmin.publicKey));
+    assert.isFalse(registry.authority.equals(provider.wallet.publicKey));
+
+    // 将管理权交还给钱包，供后续测试使用
+    await program.methods
+      .proposeAdmin(provider.wallet.publicKey)
+      .accounts({ authority: newAdmin.publicKey, ...adminAccounts })
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: provider.wallet.publicKey,
+        accessRegistry: accessRegistryPda,
+        ...adminAccounts,
+      })
+      .rpc();
+    authorityState = await program.account.authorityState.fetch(authorityPda);
+    assert.isTrue(authorityState.admin.equals(provider.wallet.publicKey));
+    registry = await program.account.accessRegistryState.fetch(accessRegistryPda);
+    assert.isTrue(registry.authority.equals(provider.wallet.publicKey));
+  });
+
+  it("Accept admin on a mint without an access registry", async () => {
+    const { mint, authority } = await initializeMint();
+    const newAdmin = Keypair.generate();
+    const adminAccounts = {
+      authorityState: authority,
+      tokenMint: mint.publicKey,
+    };
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ authority: provider.wallet.publicKey, ...adminAccounts })
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({
+        newAdmin: newAdmin.publicKey,
+        accessRegistry: null,
+        ...adminAccounts,
+      })
+      .signers([newAdmin])
+      .rpc();
+
+    const authorityState = await program.account.authorityState.fetch(authority);
+    assert.isTrue(authorityState.admin.equals(newAdmin.publicKey));
+    assert.isNull(authorityState.pendingAdmin);
+  });
+
+  it("Migrate a legacy mint state account", async () => {
+    // 旧版账户（mint/decimals，共41字节）由Anchor.toml中的tests/fixtures在validator启动时加载
+    const legacyMint = new PublicKey(
+      "Bu5w9ujc2SxVsctYcBSwgRfU5K3Vc2a8NUiqY8QFtqvB"
+    );
+    const [legacyAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("authority"), legacyMint.toBuffer()],
+      program.programId
+    );
+    const [legacyMintState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_state"), legacyMint.toBuffer()],
+      program.programId
+    );
+    const migrateAccounts = {
+      tokenMint: legacyMint,
+      authorityState: legacyAuthority,
+      mintState: legacyMintState,
+      systemProgram: SystemProgram.programId,
+    };
+    let info = await provider.connection.getAccountInfo(legacyMintState);
+    assert.equal(info.data.length, 41);
+
+    // 非管理员不能迁移
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .migrateMintState()
+        .accounts({ authority: stranger.publicKey, ...migrateAccounts })
+        .signers([stranger])
+        .rpc();
+      assert.fail("non-admin migration should fail");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+
+    await program.methods
+      .migrateMintState()
+      .accounts({ authority: provider.wallet.publicKey, ...migrateAccounts })
+      .rpc();
+    const mintState = await program.account.mintState.fetch(legacyMintState);
+    assert.isTrue(mintState.mint.equals(legacyMint));
+    assert.equal(mintState.decimals, 6);
+    assert.equal(mintState.maxSupply.toString(), "18446744073709551615");
+    assert.equal(mintState.mintRateLimit.limit.toNumber(), 0);
+    info = await provider.connection.getAccountInfo(legacyMintState);
+    assert.isAtLeast(
+      info.lamports,
+      await provider.connection.getMinimumBalanceForRentExemption(
+        info.data.length
+      )
+    );
+
+    // 当前布局的账户不能重复迁移
+    try {
+      await program.methods
+        .migrateMintState()
+        .accounts({ authority: provider.wallet.publicKey, ...migrateAccounts })
+        .rpc();
+      assert.fail("second migration should fail");
+    } catch (error) {
+      assert.include(error.toString(), "AlreadyMigrated");
+    }
+  });
+
   it("Create Recipient Token Account", async () => {
     try {
       // 获取关联代币账户地址
@@ -460,7 +1199,7 @@ describe("WUSD Token Test", () => {
 
       // 执行铸币操作
       const tx = await program.methods
-        .mint(new anchor.BN(10000000000), authorityBump)
+        .mint(new anchor.BN(10000000000), authorityBump, "deposit-0001")
         .accounts({
           authority: provider.wallet.publicKey,
           tokenMint: mintKeypair.publicKey,
@@ -470,6 +1209,13 @@ describe("WUSD Token Test", () => {
           mintState: mintStatePda,
           pauseState: pauseStatePda,
           accessRegistry: accessRegistryPda,
+          minterState: minterPda(provider.wallet.publicKey),
+          minterRole: rolePda(ROLES.minter),
+          minterRateLimit: minterRateLimitPda(provider.wallet.publicKey),
+          recipientBlacklist: blacklistPda(recipientKeypair.publicKey),
+          recipientAllowlist: allowlistPda(recipientKeypair.publicKey),
+          recipientKyc: kycPda(recipientKeypair.publicKey),
+          recipientKycLimits: kycTierPda(),
         })
         .signers([provider.wallet.payer])
         .rpc();
@@ -477,6 +1223,12 @@ describe("WUSD Token Test", () => {
       await provider.connection.confirmTransaction(tx);
       console.log("Successfully minted WUSD tokens");
 
+      // 铸币额度应被扣减
+      const minterState = await program.account.minterState.fetch(
+        minterPda(provider.wallet.publicKey)
+      );
+      assert.equal(minterState.allowance.toString(), "10000000000");
+
       // 验证铸币结果
       const tokenAccount = await provider.connection.getTokenAccountBalance(
         recipientTokenAccount
@@ -488,50 +1240,272 @@ describe("WUSD Token Test", () => {
     }
   });
 
-  it("Transfer WUSD tokens", async () => {
+  it("Enforce max supply", async () => {
+    const setMaxSupply = (maxSupply: anchor.BN) =>
+      program.methods
+        .setMaxSupply(maxSupply)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          mintState: mintStatePda,
+        })
+        .rpc();
+
+    // 将上限设为当前供应量，之后的铸币应失败
+    const supply = await provider.connection.getTokenSupply(
+      mintKeypair.publicKey
+    );
+    await setMaxSupply(new anchor.BN(supply.value.amount));
+
     try {
-      // 跳过为 recipientKeypair 请求空投，在devnet上使用已有的SOL
-      console.log("Skipping airdrop to recipient on devnet - please ensure your wallet already has SOL");
-      console.log("Recipient address:", recipientKeypair.publicKey.toString());
-      await sleep(1000); // 等待一下以确保连接稳定
+      await mintTokens(new anchor.BN(1));
+      assert.fail("Mint above max supply should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "MaxSupplyExceeded");
+    }
 
-      // 检查当前操作员列表
-      const accessRegistry = await program.account.accessRegistryState.fetch(
-        accessRegistryPda
-      );
+    // 恢复为不设上限
+    await setMaxSupply(new anchor.BN("18446744073709551615"));
+  });
 
-      // 如果操作员列表已满，移除第一个操作员
-      if (accessRegistry.operatorCount >= 10) {
-        const removeOperatorTx = await program.methods
-          .removeOperator(accessRegistry.operators[0])
-          .accounts({
-            authority: provider.wallet.publicKey,
-            authorityState: authorityPda,
-            accessRegistry: accessRegistryPda,
-            operator: accessRegistry.operators[0],
-          })
-          .rpc();
+  it("Block minting to a blacklisted wallet", async () => {
+    await grantRole(ROLES.blacklister, provider.wallet.publicKey);
+    const blacklistAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      blacklisterRole: rolePda(ROLES.blacklister),
+      blacklistEntry: blacklistPda(recipientKeypair.publicKey),
+    };
+
+    await program.methods
+      .blacklist(recipientKeypair.publicKey)
+      .accounts({
+        ...blacklistAccounts,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    try {
+      await mintTokens(new anchor.BN(1));
+      assert.fail("Mint to a blacklisted wallet should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "Blacklisted");
+    }
 
-        await provider.connection.confirmTransaction(removeOperatorTx);
-        console.log(
-          "Removed operator:",
-          accessRegistry.operators[0].toString()
-        );
-        await sleep(1000);
+    // 移出黑名单后恢复正常
+    await program.methods
+      .unblacklist(recipientKeypair.publicKey)
+      .accounts(blacklistAccounts)
+      .rpc();
+    await mintTokens(new anchor.BN(1));
+  });
+
+  it("Enforce minter rate limit", async () => {
+    const setMinterRateLimit = (limit: number, window: number) =>
+      program.methods
+        .setMinterRateLimit(
+          provider.wallet.publicKey,
+          new anchor.BN(limit),
+          new anchor.BN(window)
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          minterRateLimit: minterRateLimitPda(provider.wallet.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // 任意24小时内最多铸造1 WUSD
+    await setMinterRateLimit(1000000, 86400);
+    await mintTokens(new anchor.BN(1000000));
+
+    try {
+      await mintTokens(new anchor.BN(1000000));
+      assert.fail("Mint above rate limit should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "MintRateLimitExceeded");
+    }
+
+    // 移除并重新配置铸币者不会重置速率限制
+    const minterAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      masterMinterRole: rolePda(ROLES.masterMinter),
+      minterState: minterPda(provider.wallet.publicKey),
+      minterRole: rolePda(ROLES.minter),
+    };
+    const { allowance } = await program.account.minterState.fetch(
+      minterPda(provider.wallet.publicKey)
+    );
+    await program.methods
+      .removeMinter(provider.wallet.publicKey)
+      .accounts(minterAccounts)
+      .rpc();
+    await program.methods
+      .configureMinter(provider.wallet.publicKey, allowance)
+      .accounts({ ...minterAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    try {
+      await mintTokens(new anchor.BN(1000000));
+      assert.fail("Re-configured minter should keep its rate limit");
+    } catch (error) {
+      assert.include(error.toString(), "MintRateLimitExceeded");
+    }
+
+    // 取消限制
+    await setMinterRateLimit(0, 0);
+  });
+
+  it("Slide the minter rate limit window across a window boundary", async () => {
+    // 48秒窗口，每个时间桶2秒
+    const window = 48;
+    const setMinterRateLimit = (limit: number, window: number) =>
+      program.methods
+        .setMinterRateLimit(
+          provider.wallet.publicKey,
+          new anchor.BN(limit),
+          new anchor.BN(window)
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          minterRateLimit: minterRateLimitPda(provider.wallet.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const chainTime = async () => {
+      const clock = await provider.connection.getAccountInfo(
+        anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        "confirmed"
+      );
+      return Number(clock.data.readBigInt64LE(32));
+    };
+    const waitUntil = async (timestamp: number) => {
+      while ((await chainTime()) < timestamp) {
+        await sleep(500);
       }
+    };
 
-      // 为发送方账户添加转账权限
-      const addOperatorTx = await program.methods
-        .addOperator(recipientKeypair.publicKey)
+    // 窗口长度须为时间桶数量的整数倍
+    try {
+      await setMinterRateLimit(1000000, 50);
+      assert.fail("Window not divisible into buckets should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidRateLimit");
+    }
+    await setMinterRateLimit(1000000, window);
+
+    // 在按纪元切分的固定窗口的后半段用满额度
+    let now = await chainTime();
+    const windowStart = now - (now % window);
+    await waitUntil(
+      now % window < window / 2 ? windowStart + window / 2 : windowStart + window * 1.5
+    );
+    const mintedAfter = await chainTime();
+    await mintTokens(new anchor.BN(1000000));
+
+    // 越过固定窗口边界后，边界前的用量仍在滑动窗口内
+    await waitUntil(mintedAfter - (mintedAfter % window) + window + 1);
+    try {
+      await mintTokens(new anchor.BN(1));
+      assert.fail("Usage before the boundary should still count");
+    } catch (error) {
+      assert.include(error.toString(), "MintRateLimitExceeded");
+    }
+
+    // 铸币所在的时间桶滑出窗口后额度恢复
+    now = await chainTime();
+    await waitUntil(now - (now % 2) + window);
+    await mintTokens(new anchor.BN(1000000));
+
+    await setMinterRateLimit(0, 0);
+  });
+
+  it("Require the Minter role to mint", async () => {
+    const manageMinterRole = (method: "grantRole" | "revokeRole") =>
+      program.methods[method](ROLES.minter.name, provider.wallet.publicKey)
         .accounts({
           authority: provider.wallet.publicKey,
           authorityState: authorityPda,
-          accessRegistry: accessRegistryPda,
-          operator: recipientKeypair.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          roleState: rolePda(ROLES.minter),
+          adminRoleState: null,
+          systemProgram: SystemProgram.programId,
         })
         .rpc();
 
-      await provider.connection.confirmTransaction(addOperatorTx);
+    // 撤销Minter角色后，即使仍有铸币额度也无法铸币
+    await manageMinterRole("revokeRole");
+    try {
+      await mintTokens(new anchor.BN(1000000));
+      assert.fail("mint without the Minter role should fail");
+    } catch (error) {
+      assert.include(error.toString(), "NotMinter");
+    }
+
+    await manageMinterRole("grantRole");
+    await mintTokens(new anchor.BN(1000000));
+
+    // 由主铸币者配置、但不是操作员的铸币者可以铸币
+    const minter = Keypair.generate();
+    const minterAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      masterMinterRole: rolePda(ROLES.masterMinter),
+      minterState: minterPda(minter.publicKey),
+      minterRole: rolePda(ROLES.minter),
+    };
+    await program.methods
+      .configureMinter(minter.publicKey, new anchor.BN(1000000))
+      .accounts({ ...minterAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    assert.isNull(
+      await provider.connection.getAccountInfo(operatorPda(minter.publicKey))
+    );
+    await program.methods
+      .mint(new anchor.BN(1000000), authorityBump, null)
+      .accounts({
+        authority: minter.publicKey,
+        tokenMint: mintKeypair.publicKey,
+        tokenAccount: recipientTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        authorityState: authorityPda,
+        mintState: mintStatePda,
+        pauseState: pauseStatePda,
+        accessRegistry: accessRegistryPda,
+        minterState: minterPda(minter.publicKey),
+        minterRole: rolePda(ROLES.minter),
+        minterRateLimit: minterRateLimitPda(minter.publicKey),
+        recipientBlacklist: blacklistPda(recipientKeypair.publicKey),
+        recipientAllowlist: allowlistPda(recipientKeypair.publicKey),
+        recipientKyc: kycPda(recipientKeypair.publicKey),
+        recipientKycLimits: kycTierPda(),
+      })
+      .signers([minter])
+      .rpc();
+    const minterState = await program.account.minterState.fetch(
+      minterPda(minter.publicKey)
+    );
+    assert.equal(minterState.allowance.toNumber(), 0);
+
+    await program.methods
+      .removeMinter(minter.publicKey)
+      .accounts(minterAccounts)
+      .rpc();
+  });
+
+  it("Transfer WUSD tokens", async () => {
+    try {
+      // 跳过为 recipientKeypair 请求空投，在devnet上使用已有的SOL
+      console.log("Skipping airdrop to recipient on devnet - please ensure your wallet already has SOL");
+      console.log("Recipient address:", recipientKeypair.publicKey.toString());
+      await sleep(1000); // 等待一下以确保连接稳定
+
+      // 为发送方账户添加转账权限
+      await addOperator(recipientKeypair.publicKey, "sender");
       console.log("Transfer access granted to sender");
 
       // 创建新的接收账户
@@ -638,7 +1612,7 @@ describe("WUSD Token Test", () => {
       const transferAmount = new anchor.BN(5000000); // 5 WUSD
 
       const transferTx = await program.methods
-        .transfer(transferAmount)
+        .transfer(transferAmount, "INV-0001")
         .accounts({
           from: recipientKeypair.publicKey,
           to: newRecipient.publicKey,
@@ -646,10 +1620,22 @@ describe("WUSD Token Test", () => {
           toToken: newRecipientTokenAccount,
           tokenProgram: TOKEN_2022_PROGRAM_ID,
           tokenMint: mintKeypair.publicKey,
+          mintState: mintStatePda,
           pauseState: pauseStatePda,
           accessRegistry: accessRegistryPda,
           fromFreezeState: fromFreezeState,
           toFreezeState: toFreezeState,
+          fromBlacklist: blacklistPda(recipientKeypair.publicKey),
+          toBlacklist: blacklistPda(newRecipient.publicKey),
+          recipientAllowlist: allowlistPda(newRecipient.publicKey),
+          fromKyc: kycPda(recipientKeypair.publicKey),
+          fromKycLimits: kycTierPda(),
+          fromKycOutflow: kycOutflowPda(recipientKeypair.publicKey),
+          toKyc: kycPda(newRecipient.publicKey),
+          toKycLimits: kycTierPda(),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+          memoProgram: MEMO_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
         .signers([recipientKeypair])
         .rpc();
@@ -693,56 +1679,131 @@ describe("WUSD Token Test", () => {
     }
   });
 
+  it("Require memos on incoming transfers", async () => {
+    const receiver = Keypair.generate();
+    const receiverTokenAccount = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      receiver.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    // 接收者需支付memo-transfer扩展的追加租金
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: receiver.publicKey,
+          lamports: LAMPORTS_PER_SOL * 0.1,
+        }),
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          receiverTokenAccount,
+          receiver.publicKey,
+          mintKeypair.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+    const freezePda = (tokenAccount: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("freeze"), tokenAccount.toBuffer()],
+        program.programId
+      )[0];
+    await program.methods
+      .initializeFreezeState()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        freezeState: freezePda(receiverTokenAccount),
+        tokenAccount: receiverTokenAccount,
+        payer: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    await program.methods
+      .setMemoRequired(true)
+      .accounts({
+        owner: receiver.publicKey,
+        tokenAccount: receiverTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([receiver])
+      .rpc();
+
+    const transfer = (amount: number, memo: string | null) =>
+      program.methods
+        .transfer(new anchor.BN(amount), memo)
+        .accounts({
+          from: recipientKeypair.publicKey,
+          to: receiver.publicKey,
+          fromToken: recipientTokenAccount,
+          toToken: receiverTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenMint: mintKeypair.publicKey,
+          mintState: mintStatePda,
+          pauseState: pauseStatePda,
+          accessRegistry: accessRegistryPda,
+          fromFreezeState: freezePda(recipientTokenAccount),
+          toFreezeState: freezePda(receiverTokenAccount),
+          fromBlacklist: blacklistPda(recipientKeypair.publicKey),
+          toBlacklist: blacklistPda(receiver.publicKey),
+          recipientAllowlist: allowlistPda(receiver.publicKey),
+          fromKyc: kycPda(recipientKeypair.publicKey),
+          fromKycLimits: kycTierPda(),
+          fromKycOutflow: kycOutflowPda(recipientKeypair.publicKey),
+          toKyc: kycPda(receiver.publicKey),
+          toKycLimits: kycTierPda(),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+          memoProgram: memo === null ? null : MEMO_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([recipientKeypair])
+        .rpc();
+    const balance = async () =>
+      (await provider.connection.getTokenAccountBalance(receiverTokenAccount))
+        .value.amount;
+
+    // 未附带备注的转账被Token-2022拒绝
+    await expectFailure(
+      transfer(1000000, null),
+      "No memo in previous instruction"
+    );
+
+    // 备注由log_memo在转账前以CPI记录，256字节以内均可
+    await transfer(1000000, "INV-0002");
+    await transfer(1000000, "x".repeat(256));
+    assert.equal(await balance(), "2000000");
+
+    // 超过256字节的备注在转账前被拒绝
+    await expectFailure(transfer(1000000, "x".repeat(257)), "MemoTooLong");
+    assert.equal(await balance(), "2000000");
+
+    // 关闭后无需备注
+    await program.methods
+      .setMemoRequired(false)
+      .accounts({
+        owner: receiver.publicKey,
+        tokenAccount: receiverTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([receiver])
+      .rpc();
+    await transfer(1000000, null);
+    assert.equal(await balance(), "3000000");
+  });
+
   it("Test transfer_from functionality", async () => {
     try {
       // 跳过为 recipientKeypair 请求空投，在devnet上使用已有的SOL
       console.log("Skipping airdrop to recipient on devnet - please ensure your wallet already has SOL");
       console.log("Recipient address:", recipientKeypair.publicKey.toString());
 
-      // 检查当前操作员列表
-      const accessRegistry = await program.account.accessRegistryState.fetch(
-        accessRegistryPda
-      );
-      console.log("Current operators:", {
-        operatorCount: accessRegistry.operatorCount,
-        operators: accessRegistry.operators
-          .slice(0, accessRegistry.operatorCount)
-          .map((op) => op.toString()),
-      });
-
-      // 如果操作员列表已满，移除前两个操作员
-      if (accessRegistry.operatorCount >= 9) {
-        for (let i = 0; i < 2; i++) {
-          const operator = accessRegistry.operators[i];
-          const removeOperatorTx = await program.methods
-            .removeOperator(operator)
-            .accounts({
-              authority: provider.wallet.publicKey,
-              authorityState: authorityPda,
-              accessRegistry: accessRegistryPda,
-              operator: operator,
-            })
-            .rpc();
-
-          await provider.connection.confirmTransaction(removeOperatorTx);
-          console.log(`Removed operator ${i + 1}:`, operator.toString());
-          await sleep(1000);
-        }
-      }
-
       // 为 spender 添加操作员权限
       const spender = Keypair.generate(); // 创建一个新的spender账户
-      const addOperatorTx = await program.methods
-        .addOperator(spender.publicKey)
-        .accounts({
-          authority: provider.wallet.publicKey,
-          authorityState: authorityPda,
-          accessRegistry: accessRegistryPda,
-          operator: spender.publicKey,
-        })
-        .rpc();
-
-      await provider.connection.confirmTransaction(addOperatorTx);
+      await addOperator(spender.publicKey, "spender");
       console.log("Added spender as operator");
       await sleep(1000);
 
@@ -794,28 +1855,14 @@ describe("WUSD Token Test", () => {
       await provider.connection.confirmTransaction(signature, "confirmed");
       console.log("Created spender token account");
 
-      // 创建 permit 和 allowance 状态账户的 PDA
-      const [allowanceStatePda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("allowance"),
-          recipientKeypair.publicKey.toBuffer(),
-          spender.publicKey.toBuffer(),
-        ],
-        program.programId
-      );
-
-      const [permitPda] = PublicKey.findProgramAddressSync(
-        [
-          Buffer.from("permit"),
-          recipientKeypair.publicKey.toBuffer(),
-          spender.publicKey.toBuffer(),
-        ],
-        program.programId
+      // 创建 allowance 状态账户的 PDA
+      const allowanceStatePda = allowancePda(
+        recipientKeypair.publicKey,
+        spender.publicKey
       );
 
       console.log("Debug PDA addresses:", {
         allowanceStatePda: allowanceStatePda.toString(),
-        permitPda: permitPda.toString(),
         owner: recipientKeypair.publicKey.toString(),
         spender: spender.publicKey.toString(),
       });
@@ -905,80 +1952,82 @@ describe("WUSD Token Test", () => {
 
       // 创建PermitScope对象
       const permitScope = {
-        one_time: false,
+        oneTime: false,
         permanent: true,
         transfer: true,
         burn: false,
         all: false,
       };
 
-      // 创建permit
-      const permitTx = await program.methods
-        .permit({
-          amount: permitAmount,
-          deadline: new anchor.BN(deadline),
-          nonce: null,
-          scope: permitScope,
-          signature: new Uint8Array(64).fill(0),
-          public_key: new Uint8Array(32).fill(0),
-        })
-        .accounts({
-          owner: recipientKeypair.publicKey,
-          spender: spender.publicKey,
-          allowance: allowanceStatePda,
-          permitState: permitPda,
-          mintState: mintStatePda,
-          tokenProgram: TOKEN_2022_PROGRAM_ID,
-          systemProgram: SystemProgram.programId,
-          clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
-        })
-        .signers([recipientKeypair])
-        .rpc();
+      // owner在链下对许可消息签名，由钱包作为中继者提交
+      const permitTx = await sendPermit(
+        recipientKeypair,
+        spender.publicKey,
+        permitAmount,
+        deadline,
+        null,
+        permitScope
+      );
 
       await provider.connection.confirmTransaction(permitTx);
       console.log("Permit created successfully");
 
+      // 许可范围应按请求保存
+      const allowanceState = await program.account.allowanceState.fetch(
+        allowanceStatePda
+      );
+      assert.deepEqual(allowanceState.scope, permitScope);
+
       // 执行transfer_from操作
       const transferAmount = new anchor.BN(5000000); // 5 WUSD
       try {
-        // 在执行transfer_from之前，先使用标准的SPL Token approve指令授权spender
-        const approveIx = createApproveInstruction(
-          recipientTokenAccount,
-          spender.publicKey,
-          recipientKeypair.publicKey,
-          transferAmount.toNumber(),
-          [],
-          TOKEN_2022_PROGRAM_ID
-        );
-
-        // 先执行approve指令
-        const approveTx = new anchor.web3.Transaction().add(approveIx);
-        const approveSignature = await provider.connection.sendTransaction(
-          approveTx,
-          [recipientKeypair]
+        // owner将代币账户一次性委托给程序控制的PDA
+        const [delegatePda] = PublicKey.findProgramAddressSync(
+          [Buffer.from("delegate"), mintKeypair.publicKey.toBuffer()],
+          program.programId
         );
-        await provider.connection.confirmTransaction(approveSignature, "confirmed");
-        console.log("Approve transaction confirmed:", approveSignature);
-
-        // 等待一段时间确保approve生效
-        await sleep(2000);
+        const approveDelegateTx = await program.methods
+          .approveDelegate()
+          .accounts({
+            owner: recipientKeypair.publicKey,
+            tokenAccount: recipientTokenAccount,
+            tokenMint: mintKeypair.publicKey,
+            delegate: delegatePda,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+          })
+          .signers([recipientKeypair])
+          .rpc();
+        await provider.connection.confirmTransaction(approveDelegateTx, "confirmed");
+        console.log("Delegate approved:", delegatePda.toString());
 
         // 然后执行transfer_from
         const transferFromTx = await program.methods
-          .transferFrom(transferAmount)
+          .transferFrom(transferAmount, null)
           .accounts({
             spender: spender.publicKey,
             owner: recipientKeypair.publicKey,
             fromToken: recipientTokenAccount,
             toToken: toTokenAccount,
-            permit: permitPda,
+            allowance: allowanceStatePda,
             mintState: mintStatePda,
             pauseState: pauseStatePda,
             accessRegistry: accessRegistryPda,
             tokenProgram: TOKEN_2022_PROGRAM_ID,
             tokenMint: mintKeypair.publicKey,
+            delegate: delegatePda,
             fromFreezeState: fromFreezeState,
             toFreezeState: toFreezeState,
+            ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+            recipientBlacklist: blacklistPda(spender.publicKey),
+            recipientAllowlist: allowlistPda(spender.publicKey),
+            spenderBlacklist: blacklistPda(spender.publicKey),
+            ownerKyc: kycPda(recipientKeypair.publicKey),
+            ownerKycLimits: kycTierPda(),
+            ownerKycOutflow: kycOutflowPda(recipientKeypair.publicKey),
+            recipientKyc: kycPda(spender.publicKey),
+            recipientKycLimits: kycTierPda(),
+            operatorRecord: operatorPda(spender.publicKey),
+            memoProgram: null,
             systemProgram: SystemProgram.programId,
           })
           .signers([spender]) // 只使用spender作为签名者
@@ -1030,20 +2079,1090 @@ describe("WUSD Token Test", () => {
     }
   });
 
-  it("Set burn access", async () => {
+  it("Reject replayed and cancelled permits", async () => {
+    const spender = Keypair.generate();
+    const amount = new anchor.BN(1000000); // 1 WUSD
+    const deadline = Math.floor(Date.now() / 1000) + 3600;
+    const scope = {
+      oneTime: false,
+      permanent: true,
+      transfer: true,
+      burn: false,
+      all: false,
+    };
+
+    // 首个许可使用随机数0
+    const tx = await sendPermit(
+      recipientKeypair,
+      spender.publicKey,
+      amount,
+      deadline,
+      new anchor.BN(0),
+      scope
+    );
+    await provider.connection.confirmTransaction(tx, "confirmed");
+
+    // 重放同一随机数的签名应被拒绝
     try {
-      // 添加销毁权限
-      const tx = await program.methods
-        .addOperator(recipientKeypair.publicKey) 
+      await sendPermit(
+        recipientKeypair,
+        spender.publicKey,
+        amount,
+        deadline,
+        new anchor.BN(0),
+        scope
+      );
+      assert.fail("Replayed permit should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidNonce");
+    }
+
+    // 已过期的许可应被拒绝
+    try {
+      await sendPermit(
+        recipientKeypair,
+        spender.publicKey,
+        amount,
+        Math.floor(Date.now() / 1000) - 60,
+        new anchor.BN(1),
+        scope
+      );
+      assert.fail("Expired permit should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "PermitExpired");
+    }
+
+    // owner作废随机数1~4，之后随机数为1的签名失效
+    const invalidateTx = await program.methods
+      .invalidateNonces(new anchor.BN(5))
+      .accounts({
+        owner: recipientKeypair.publicKey,
+        spender: spender.publicKey,
+        tokenMint: mintKeypair.publicKey,
+        allowance: allowancePda(recipientKeypair.publicKey, spender.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([recipientKeypair])
+      .rpc();
+    await provider.connection.confirmTransaction(invalidateTx, "confirmed");
+
+    try {
+      await sendPermit(
+        recipientKeypair,
+        spender.publicKey,
+        amount,
+        deadline,
+        new anchor.BN(1),
+        scope
+      );
+      assert.fail("Cancelled permit should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidNonce");
+    }
+  });
+
+  it("Require CAN_RELAY_PERMIT for third-party relayers", async () => {
+    const spender = Keypair.generate();
+    const relayer = Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: relayer.publicKey,
+          lamports: LAMPORTS_PER_SOL * 0.05,
+        })
+      )
+    );
+    const amount = new anchor.BN(1000000);
+    const deadline = Math.floor(Date.now() / 1000) + 3600;
+    const scope = {
+      oneTime: false,
+      permanent: true,
+      transfer: true,
+      burn: false,
+      all: false,
+    };
+
+    // 非操作员中继者被拒绝
+    try {
+      await sendPermit(
+        recipientKeypair,
+        spender.publicKey,
+        amount,
+        deadline,
+        new anchor.BN(0),
+        scope,
+        relayer
+      );
+      assert.fail("relayer without CAN_RELAY_PERMIT should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "AccessDenied");
+    }
+
+    // 拥有CAN_RELAY_PERMIT权限的操作员可以中继
+    await addOperator(relayer.publicKey, "relayer", PERMISSIONS.relayPermit);
+    await sendPermit(
+      recipientKeypair,
+      spender.publicKey,
+      amount,
+      deadline,
+      new anchor.BN(0),
+      scope,
+      relayer
+    );
+
+    // owner自行提交无需该权限
+    await sendPermit(
+      recipientKeypair,
+      spender.publicKey,
+      amount,
+      deadline,
+      new anchor.BN(1),
+      scope,
+      recipientKeypair
+    );
+    const allowance = await program.account.allowanceState.fetch(
+      allowancePda(recipientKeypair.publicKey, spender.publicKey)
+    );
+    assert.equal(allowance.nonce.toNumber(), 2);
+  });
+
+  it("Approve, increase, decrease and revoke allowance", async () => {
+    const spender = Keypair.generate();
+    const allowance = allowancePda(recipientKeypair.publicKey, spender.publicKey);
+    const accounts = {
+      owner: recipientKeypair.publicKey,
+      spender: spender.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      allowance,
+      systemProgram: SystemProgram.programId,
+    };
+    const fetchAmount = async () =>
+      (await program.account.allowanceState.fetch(allowance)).amount.toNumber();
+
+    await program.methods
+      .approve(new anchor.BN(3000000))
+      .accounts(accounts)
+      .signers([recipientKeypair])
+      .rpc();
+    assert.equal(await fetchAmount(), 3000000);
+
+    await program.methods
+      .increaseAllowance(new anchor.BN(2000000))
+      .accounts(accounts)
+      .signers([recipientKeypair])
+      .rpc();
+    assert.equal(await fetchAmount(), 5000000);
+
+    await program.methods
+      .decreaseAllowance(new anchor.BN(1000000))
+      .accounts(accounts)
+      .signers([recipientKeypair])
+      .rpc();
+    assert.equal(await fetchAmount(), 4000000);
+
+    await program.methods
+      .revoke()
+      .accounts(accounts)
+      .signers([recipientKeypair])
+      .rpc();
+    assert.equal(await fetchAmount(), 0);
+  });
+
+  it("Scope allowances to a single mint", async () => {
+    const spender = Keypair.generate();
+    const otherMint = Keypair.generate();
+    const mintSize = 82;
+    const createMintTx = new anchor.web3.Transaction()
+      .add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: otherMint.publicKey,
+          space: mintSize,
+          lamports: await provider.connection.getMinimumBalanceForRentExemption(
+            mintSize
+          ),
+          programId: TOKEN_2022_PROGRAM_ID,
+        })
+      )
+      .add(
+        createInitializeMint2Instruction(
+          otherMint.publicKey,
+          6,
+          provider.wallet.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      );
+    await provider.sendAndConfirm(createMintTx, [otherMint]);
+
+    await program.methods
+      .approve(new anchor.BN(1000000))
+      .accounts({
+        owner: recipientKeypair.publicKey,
+        spender: spender.publicKey,
+        tokenMint: mintKeypair.publicKey,
+        allowance: allowancePda(recipientKeypair.publicKey, spender.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([recipientKeypair])
+      .rpc();
+
+    // 另一个mint上不存在对应的授权
+    assert.isNull(
+      await provider.connection.getAccountInfo(
+        allowancePda(recipientKeypair.publicKey, spender.publicKey, otherMint.publicKey)
+      )
+    );
+
+    // mint A的授权账户不能在mint B上使用
+    try {
+      await program.methods
+        .decreaseAllowance(new anchor.BN(1))
         .accounts({
-          authority: provider.wallet.publicKey,
+          owner: recipientKeypair.publicKey,
+          spender: spender.publicKey,
+          tokenMint: otherMint.publicKey,
+          allowance: allowancePda(recipientKeypair.publicKey, spender.publicKey),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([recipientKeypair])
+        .rpc();
+      assert.fail("allowance of another mint should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "ConstraintSeeds");
+    }
+  });
+
+  it("Request, reject and fulfill redemptions", async () => {
+    await grantRole(ROLES.burner, provider.wallet.publicKey);
+
+    const redemptionVault = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      authorityPda,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const requestPda = (requestId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("redemption"),
+          mintKeypair.publicKey.toBuffer(),
+          recipientKeypair.publicKey.toBuffer(),
+          requestId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      )[0];
+    const requestRedemption = (requestId: anchor.BN, amount: anchor.BN) =>
+      program.methods
+        .requestRedemption(requestId, amount, Array.from(Buffer.alloc(32, 1)))
+        .accounts({
+          owner: recipientKeypair.publicKey,
+          tokenAccount: recipientTokenAccount,
+          tokenMint: mintKeypair.publicKey,
           authorityState: authorityPda,
+          redemptionVault,
+          redemptionRequest: requestPda(requestId),
+          pauseState: pauseStatePda,
           accessRegistry: accessRegistryPda,
-          operator: recipientKeypair.publicKey,
+          ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
         })
+        .signers([recipientKeypair])
         .rpc();
+    const resolveAccounts = (requestId: anchor.BN) => ({
+      operator: provider.wallet.publicKey,
+      burnerRole: rolePda(ROLES.burner),
+      tokenMint: mintKeypair.publicKey,
+      authorityState: authorityPda,
+      redemptionVault,
+      redemptionRequest: requestPda(requestId),
+      ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+      refundAccount: recipientTokenAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+    const balance = async () =>
+      Number(
+        (await provider.connection.getTokenAccountBalance(recipientTokenAccount))
+          .value.amount
+      );
 
-      await provider.connection.confirmTransaction(tx);
+    const amount = new anchor.BN(2000000); // 2 WUSD
+    const balanceBefore = await balance();
+
+    // 拒绝的赎回退回代币
+    const rejectedId = new anchor.BN(Date.now());
+    await requestRedemption(rejectedId, amount);
+    assert.equal(await balance(), balanceBefore - amount.toNumber());
+    await program.methods
+      .rejectRedemption()
+      .accounts(resolveAccounts(rejectedId))
+      .rpc();
+    assert.equal(await balance(), balanceBefore);
+    let request = await program.account.redemptionRequest.fetch(
+      requestPda(rejectedId)
+    );
+    assert.deepEqual(request.status, { rejected: {} });
+
+    // 完成的赎回销毁托管的代币
+    const fulfilledId = rejectedId.addn(1);
+    const supplyBefore = await provider.connection.getTokenSupply(
+      mintKeypair.publicKey
+    );
+    await requestRedemption(fulfilledId, amount);
+    await program.methods
+      .fulfillRedemption("WIRE-0001")
+      .accounts(resolveAccounts(fulfilledId))
+      .rpc();
+    const supplyAfter = await provider.connection.getTokenSupply(
+      mintKeypair.publicKey
+    );
+    assert.equal(
+      Number(supplyBefore.value.amount) - Number(supplyAfter.value.amount),
+      amount.toNumber()
+    );
+    request = await program.account.redemptionRequest.fetch(
+      requestPda(fulfilledId)
+    );
+    assert.deepEqual(request.status, { fulfilled: {} });
+    assert.equal(request.settlementId, "WIRE-0001");
+
+    // 退款由authority_state转出不经钩子校验，请求期间被列入黑名单或冻结的owner不能收到退款
+    const pendingId = fulfilledId.addn(1);
+    await requestRedemption(pendingId, amount);
+    await grantRole(ROLES.blacklister, provider.wallet.publicKey);
+    const blacklistAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      blacklisterRole: rolePda(ROLES.blacklister),
+      blacklistEntry: blacklistPda(recipientKeypair.publicKey),
+    };
+    await program.methods
+      .blacklist(recipientKeypair.publicKey)
+      .accounts({
+        ...blacklistAccounts,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    try {
+      await program.methods
+        .rejectRedemption()
+        .accounts(resolveAccounts(pendingId))
+        .rpc();
+      assert.fail("refund to a blacklisted owner should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "Blacklisted");
+    }
+    await program.methods
+      .unblacklist(recipientKeypair.publicKey)
+      .accounts(blacklistAccounts)
+      .rpc();
+
+    await grantRole(ROLES.freezer, provider.wallet.publicKey);
+    const [freezeState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("freeze"), recipientTokenAccount.toBuffer()],
+      program.programId
+    );
+    const freezeAccounts = {
+      authority: provider.wallet.publicKey,
+      freezeState,
+      account: recipientTokenAccount,
+      tokenMint: mintKeypair.publicKey,
+      authorityState: authorityPda,
+      freezerRole: rolePda(ROLES.freezer),
+      accessRegistry: accessRegistryPda,
+      operatorRecord: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await program.methods
+      .freezeAccount()
+      .accounts({ ...freezeAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    try {
+      await program.methods
+        .rejectRedemption()
+        .accounts(resolveAccounts(pendingId))
+        .rpc();
+      assert.fail("refund to a frozen account should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "AccountFrozen");
+    }
+    await program.methods.unfreezeAccount().accounts(freezeAccounts).rpc();
+
+    // 解除限制后可正常退款
+    const balanceBeforeRefund = await balance();
+    await program.methods
+      .rejectRedemption()
+      .accounts(resolveAccounts(pendingId))
+      .rpc();
+    assert.equal(await balance(), balanceBeforeRefund + amount.toNumber());
+    request = await program.account.redemptionRequest.fetch(
+      requestPda(pendingId)
+    );
+    assert.deepEqual(request.status, { rejected: {} });
+  });
+
+  it("Freeze and unfreeze a token account", async () => {
+    await grantRole(ROLES.freezer, provider.wallet.publicKey);
+
+    const [freezeState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("freeze"), recipientTokenAccount.toBuffer()],
+      program.programId
+    );
+    const freezeAccounts = {
+      authority: provider.wallet.publicKey,
+      freezeState,
+      account: recipientTokenAccount,
+      tokenMint: mintKeypair.publicKey,
+      authorityState: authorityPda,
+      freezerRole: rolePda(ROLES.freezer),
+      accessRegistry: accessRegistryPda,
+      operatorRecord: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    await program.methods
+      .freezeAccount()
+      .accounts({ ...freezeAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    let tokenAccount = await getAccount(
+      provider.connection,
+      recipientTokenAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isTrue(tokenAccount.isFrozen);
+    assert.isTrue(
+      (await program.account.freezeState.fetch(freezeState)).isFrozen
+    );
+
+    await program.methods.unfreezeAccount().accounts(freezeAccounts).rpc();
+    tokenAccount = await getAccount(
+      provider.connection,
+      recipientTokenAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isFalse(tokenAccount.isFrozen);
+    assert.isFalse(
+      (await program.account.freezeState.fetch(freezeState)).isFrozen
+    );
+
+    // 无Freezer角色的操作员需要CAN_FREEZE权限
+    const operator = Keypair.generate();
+    await addOperator(operator.publicKey, "transfer only", PERMISSIONS.transfer);
+    const operatorFreezeAccounts = {
+      ...freezeAccounts,
+      authority: operator.publicKey,
+      freezerRole: null,
+      operatorRecord: operatorPda(operator.publicKey),
+    };
+    try {
+      await program.methods
+        .freezeAccount()
+        .accounts({
+          ...operatorFreezeAccounts,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([operator])
+        .rpc();
+      assert.fail("operator without CAN_FREEZE should not freeze");
+    } catch (error) {
+      assert.include(error.toString(), "AccessDenied");
+    }
+
+    await program.methods
+      .updateOperatorPermissions(operator.publicKey, PERMISSIONS.freeze)
+      .accounts({
+        authority: provider.wallet.publicKey,
+        operatorAdminRole: rolePda(ROLES.operatorAdmin),
+        operatorRecord: operatorPda(operator.publicKey),
+      })
+      .rpc();
+    await program.methods
+      .freezeAccount()
+      .accounts({
+        ...operatorFreezeAccounts,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+    assert.isTrue(
+      (await program.account.freezeState.fetch(freezeState)).isFrozen
+    );
+    await program.methods
+      .unfreezeAccount()
+      .accounts(operatorFreezeAccounts)
+      .signers([operator])
+      .rpc();
+    assert.isFalse(
+      (await program.account.freezeState.fetch(freezeState)).isFrozen
+    );
+  });
+
+  it("Reject wiping an account that is not frozen", async () => {
+    await grantRole(ROLES.wiper, provider.wallet.publicKey);
+
+    const [freezeState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("freeze"), recipientTokenAccount.toBuffer()],
+      program.programId
+    );
+    try {
+      await program.methods
+        .wipeFrozenAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          wiperRole: rolePda(ROLES.wiper),
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          tokenAccount: recipientTokenAccount,
+          freezeState,
+          treasury: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("wipe of an unfrozen account should fail");
+    } catch (error) {
+      assert.include(error.toString(), "AccountNotFrozen");
+    }
+  });
+
+  it("Wipe frozen accounts by burning or moving to the treasury", async () => {
+    // initialize创建的mint以authority_state为永久委托
+    const {
+      mint,
+      authority,
+      accessRegistry,
+      roleOf,
+      ata,
+      grant,
+      holder,
+      receiver,
+      transfer,
+    } = await initializeHookMint();
+    await transfer(holder, receiver.publicKey, 4000000);
+    await grant(ROLES.freezer, provider.wallet.publicKey);
+    await grant(ROLES.wiper, provider.wallet.publicKey);
+
+    const freezePda = (tokenAccount: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("freeze"), tokenAccount.toBuffer()],
+        program.programId
+      )[0];
+    const freeze = (owner: PublicKey) =>
+      program.methods
+        .freezeAccount()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          freezeState: freezePda(ata(owner)),
+          account: ata(owner),
+          tokenMint: mint.publicKey,
+          authorityState: authority,
+          freezerRole: roleOf(ROLES.freezer),
+          accessRegistry,
+          operatorRecord: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const wipeAccounts = (owner: PublicKey, treasury: PublicKey | null) => ({
+      authority: provider.wallet.publicKey,
+      wiperRole: roleOf(ROLES.wiper),
+      tokenMint: mint.publicKey,
+      authorityState: authority,
+      tokenAccount: ata(owner),
+      freezeState: freezePda(ata(owner)),
+      treasury,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    });
+    const accountOf = (owner: PublicKey) =>
+      getAccount(
+        provider.connection,
+        ata(owner),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+    const supply = async () =>
+      (
+        await getMint(
+          provider.connection,
+          mint.publicKey,
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).supply.toString();
+
+    // 未提供国库账户时销毁余额，供应量随之减少
+    await freeze(holder.publicKey);
+    assert.equal(await supply(), "10000000");
+    await program.methods
+      .wipeFrozenAccount()
+      .accounts(wipeAccounts(holder.publicKey, null))
+      .rpc();
+    let wiped = await accountOf(holder.publicKey);
+    assert.equal(wiped.amount.toString(), "0");
+    assert.isTrue(wiped.isFrozen);
+    assert.equal(await supply(), "4000000");
+
+    // 提供国库账户时转入国库，转账钩子所需的额外账户按列表解析后附加
+    const treasury = ata(provider.wallet.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          treasury,
+          provider.wallet.publicKey,
+          mint.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+    await freeze(receiver.publicKey);
+    const hookAccounts = (
+      await createTransferCheckedWithTransferHookInstruction(
+        provider.connection,
+        ata(receiver.publicKey),
+        mint.publicKey,
+        treasury,
+        authority,
+        4000000,
+        6,
+        [],
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).keys.slice(4);
+    await program.methods
+      .wipeFrozenAccount()
+      .accounts(wipeAccounts(receiver.publicKey, treasury))
+      .remainingAccounts(hookAccounts)
+      .rpc();
+    wiped = await accountOf(receiver.publicKey);
+    assert.equal(wiped.amount.toString(), "0");
+    assert.isTrue(wiped.isFrozen);
+    assert.equal(
+      (await accountOf(provider.wallet.publicKey)).amount.toString(),
+      "4000000"
+    );
+    assert.equal(await supply(), "4000000");
+  });
+
+  it("Reject a transfer fee above 100%", async () => {
+    await grantRole(ROLES.feeManager, provider.wallet.publicKey);
+
+    try {
+      await program.methods
+        .setTransferFee(10001, new anchor.BN(0))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          feeManagerRole: rolePda(ROLES.feeManager),
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("fee above 10000 basis points should fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidTransferFee");
+    }
+  });
+
+  it("Withdraw withheld transfer fees to the treasury", async () => {
+    // 1%手续费，单笔上限1 WUSD
+    const {
+      mint,
+      authority,
+      pauseState,
+      mintState,
+      accessRegistry,
+      walletPda,
+      roleOf,
+      tierOf,
+      ata,
+      grant,
+      holder,
+      receiver,
+    } = await initializeHookMint(100, new anchor.BN(1000000));
+    await grant(ROLES.operatorAdmin, provider.wallet.publicKey);
+    await grant(ROLES.feeManager, provider.wallet.publicKey);
+    await program.methods
+      .addOperator(
+        holder.publicKey,
+        "holder",
+        new anchor.BN(0),
+        PERMISSIONS.transfer
+      )
+      .accounts({
+        authority: provider.wallet.publicKey,
+        operatorAdminRole: roleOf(ROLES.operatorAdmin),
+        accessRegistry,
+        operatorRecord: walletPda("operator", holder.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const freezePda = (tokenAccount: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("freeze"), tokenAccount.toBuffer()],
+        program.programId
+      )[0];
+    for (const owner of [holder.publicKey, receiver.publicKey]) {
+      await program.methods
+        .initializeFreezeState()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          freezeState: freezePda(ata(owner)),
+          tokenAccount: ata(owner),
+          payer: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    }
+
+    // 经WUSD程序转账，钩子所需的额外账户按列表解析后附加
+    const amount = 4000000;
+    const hookAccounts = (
+      await createTransferCheckedWithTransferHookInstruction(
+        provider.connection,
+        ata(holder.publicKey),
+        mint.publicKey,
+        ata(receiver.publicKey),
+        holder.publicKey,
+        amount,
+        6,
+        [],
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).keys.slice(4);
+    const signature = await program.methods
+      .transfer(new anchor.BN(amount), null)
+      .accounts({
+        from: holder.publicKey,
+        to: receiver.publicKey,
+        fromToken: ata(holder.publicKey),
+        toToken: ata(receiver.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenMint: mint.publicKey,
+        mintState,
+        pauseState,
+        accessRegistry,
+        fromFreezeState: freezePda(ata(holder.publicKey)),
+        toFreezeState: freezePda(ata(receiver.publicKey)),
+        fromBlacklist: walletPda("blacklist", holder.publicKey),
+        toBlacklist: walletPda("blacklist", receiver.publicKey),
+        recipientAllowlist: walletPda("allowlist", receiver.publicKey),
+        fromKyc: walletPda("kyc", holder.publicKey),
+        fromKycLimits: tierOf(0),
+        fromKycOutflow: walletPda("kyc_outflow", holder.publicKey),
+        toKyc: walletPda("kyc", receiver.publicKey),
+        toKycLimits: tierOf(0),
+        operatorRecord: walletPda("operator", holder.publicKey),
+        memoProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(hookAccounts)
+      .signers([holder])
+      .rpc({ commitment: "confirmed" });
+
+    // 手续费暂扣在接收账户中，TransferEvent记录的手续费与暂扣数量一致
+    const accountOf = (owner: PublicKey) =>
+      getAccount(
+        provider.connection,
+        ata(owner),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+    const fee = 40000;
+    const received = await accountOf(receiver.publicKey);
+    assert.equal(received.amount.toString(), String(amount - fee));
+    assert.equal(
+      getTransferFeeAmount(received).withheldAmount.toString(),
+      String(fee)
+    );
+    const transaction = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = Array.from(
+      new anchor.EventParser(program.programId, program.coder).parseLogs(
+        transaction.meta.logMessages
+      )
+    );
+    const transferEvent = events.find((event) => event.name === "TransferEvent");
+    assert.equal(transferEvent.data.amount.toString(), String(amount));
+    assert.equal(transferEvent.data.fee.toString(), String(fee));
+
+    // 归集接收账户的暂扣手续费并提取到国库
+    const treasury = ata(provider.wallet.publicKey);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          treasury,
+          provider.wallet.publicKey,
+          mint.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+    await program.methods
+      .withdrawWithheldFees()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        feeManagerRole: roleOf(ROLES.feeManager),
+        tokenMint: mint.publicKey,
+        authorityState: authority,
+        treasury,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts([
+        { pubkey: ata(receiver.publicKey), isSigner: false, isWritable: true },
+      ])
+      .rpc();
+
+    assert.equal(
+      (await accountOf(provider.wallet.publicKey)).amount.toString(),
+      String(fee)
+    );
+    const harvested = await accountOf(receiver.publicKey);
+    assert.equal(harvested.amount.toString(), String(amount - fee));
+    assert.equal(getTransferFeeAmount(harvested).withheldAmount.toString(), "0");
+    assert.equal(
+      (await accountOf(holder.publicKey)).amount.toString(),
+      String(10000000 - amount)
+    );
+  });
+
+  it("Initialize transfer hook extra account metas", async () => {
+    const hookProgram = anchor.workspace
+      .WusdTransferHook as Program<WusdTransferHook>;
+    const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), mintKeypair.publicKey.toBuffer()],
+      hookProgram.programId
+    );
+
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        mint: mintKeypair.publicKey,
+        extraAccountMetaList,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Execute鉴别符 + 长度 + 元素个数 + 14个额外账户
+    const info = await provider.connection.getAccountInfo(extraAccountMetaList);
+    assert.equal(info.data.length, 16 + 35 * 14);
+    assert.deepEqual(
+      Array.from(info.data.subarray(0, 8)),
+      [105, 37, 101, 197, 75, 251, 102, 26]
+    );
+    assert.equal(info.data.readUInt32LE(12), 14);
+
+    // 改写为当前列表，列表已是最新时内容不变
+    await hookProgram.methods
+      .updateExtraAccountMetaList()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        mint: mintKeypair.publicKey,
+        extraAccountMetaList,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const updated = await provider.connection.getAccountInfo(extraAccountMetaList);
+    assert.isTrue(updated.data.equals(info.data));
+  });
+
+  it("Enforce the transfer hook on direct Token-2022 transfers", async () => {
+    const {
+      mint,
+      authority,
+      pauseState,
+      accessRegistry,
+      hookProgram,
+      walletPda,
+      roleOf,
+      tierOf,
+      ata,
+      grant,
+      holder,
+      receiver,
+      transfer,
+    } = await initializeHookMint();
+    const balanceOf = async (owner: PublicKey) =>
+      (
+        await getAccount(
+          provider.connection,
+          ata(owner),
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        )
+      ).amount.toString();
+
+    // 手动构造的扩展与@solana/spl-token的解析结果一致
+    const mintInfo = await getMint(
+      provider.connection,
+      mint.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.isTrue(getTransferHook(mintInfo).programId.equals(hookProgram.programId));
+    assert.isTrue(getTransferHook(mintInfo).authority.equals(authority));
+    assert.isTrue(getMetadataPointerState(mintInfo).metadataAddress.equals(mint.publicKey));
+    assert.isTrue(getPermanentDelegate(mintInfo).delegate.equals(authority));
+
+    // 手动编码的额外账户列表按接口解析出的地址与钩子的账户结构一致
+    const freezePda = (tokenAccount: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("freeze"), tokenAccount.toBuffer()],
+        program.programId
+      )[0];
+    const transferIx = await createTransferCheckedWithTransferHookInstruction(
+      provider.connection,
+      ata(holder.publicKey),
+      mint.publicKey,
+      ata(receiver.publicKey),
+      holder.publicKey,
+      1,
+      6,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.deepEqual(
+      transferIx.keys.slice(4).map((meta) => meta.pubkey.toBase58()),
+      [
+        program.programId,
+        pauseState,
+        freezePda(ata(holder.publicKey)),
+        freezePda(ata(receiver.publicKey)),
+        walletPda("blacklist", holder.publicKey),
+        walletPda("blacklist", receiver.publicKey),
+        accessRegistry,
+        walletPda("allowlist", receiver.publicKey),
+        walletPda("kyc", holder.publicKey),
+        walletPda("kyc", receiver.publicKey),
+        tierOf(0),
+        tierOf(1),
+        tierOf(2),
+        PublicKey.findProgramAddressSync(
+          [
+            Buffer.from("kyc_outflow"),
+            mint.publicKey.toBuffer(),
+            holder.publicKey.toBuffer(),
+          ],
+          hookProgram.programId
+        )[0],
+        hookProgram.programId,
+        getExtraAccountMetaAddress(mint.publicKey, hookProgram.programId),
+      ].map((key) => key.toBase58())
+    );
+
+    await transfer(holder, receiver.publicKey, 1000000);
+    assert.equal(await balanceOf(receiver.publicKey), "1000000");
+
+    // 暂停
+    const pauseAccounts = {
+      pauseState,
+      authority: provider.wallet.publicKey,
+      tokenMint: mint.publicKey,
+      authorityState: authority,
+      pauserRole: null,
+    };
+    await program.methods.pause().accounts(pauseAccounts).rpc();
+    await expectFailure(transfer(holder, receiver.publicKey, 1000000), "ContractPaused");
+    await program.methods.unpause().accounts(pauseAccounts).rpc();
+
+    // 冻结
+    await grant(ROLES.freezer, provider.wallet.publicKey);
+    const freezeAccounts = {
+      authority: provider.wallet.publicKey,
+      freezeState: freezePda(ata(receiver.publicKey)),
+      account: ata(receiver.publicKey),
+      tokenMint: mint.publicKey,
+      authorityState: authority,
+      freezerRole: roleOf(ROLES.freezer),
+      accessRegistry,
+      operatorRecord: null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    await program.methods
+      .freezeAccount()
+      .accounts({ ...freezeAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    await expectFailure(transfer(holder, receiver.publicKey, 1000000), "frozen");
+    await program.methods.unfreezeAccount().accounts(freezeAccounts).rpc();
+
+    // 黑名单
+    await grant(ROLES.blacklister, provider.wallet.publicKey);
+    const blacklistAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mint.publicKey,
+      blacklisterRole: roleOf(ROLES.blacklister),
+      blacklistEntry: walletPda("blacklist", receiver.publicKey),
+    };
+    await program.methods
+      .blacklist(receiver.publicKey)
+      .accounts({ ...blacklistAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    await expectFailure(transfer(holder, receiver.publicKey, 1000000), "Blacklisted");
+    await program.methods
+      .unblacklist(receiver.publicKey)
+      .accounts(blacklistAccounts)
+      .rpc();
+
+    // 白名单模式
+    const setAllowlistMode = (enabled: boolean) =>
+      program.methods
+        .setAllowlistMode(enabled)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mint.publicKey,
+          authorityState: authority,
+          accessRegistry,
+        })
+        .rpc();
+    await setAllowlistMode(true);
+    await expectFailure(
+      transfer(holder, receiver.publicKey, 1000000),
+      "RecipientNotAllowlisted"
+    );
+    await grant(ROLES.allowlister, provider.wallet.publicKey);
+    await program.methods
+      .addToAllowlist([receiver.publicKey])
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mint.publicKey,
+        allowlisterRole: roleOf(ROLES.allowlister),
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([
+        {
+          pubkey: walletPda("allowlist", receiver.publicKey),
+          isSigner: false,
+          isWritable: true,
+        },
+      ])
+      .rpc();
+    await transfer(holder, receiver.publicKey, 1000000);
+    await setAllowlistMode(false);
+
+    // 限制全部解除后的转账正常到账
+    await transfer(holder, receiver.publicKey, 1000000);
+    assert.equal(await balanceOf(receiver.publicKey), "3000000");
+    assert.equal(await balanceOf(holder.publicKey), "7000000");
+  });
+
+  it("Set burn access", async () => {
+    try {
+      // 添加销毁权限
+      await addOperator(recipientKeypair.publicKey, "burner");
       console.log("Burn access granted");
     } catch (error) {
       console.error("Failed to set burn access:", error);
@@ -1077,6 +3196,8 @@ describe("WUSD Token Test", () => {
           pauseState: pauseStatePda,
           accessRegistry: accessRegistryPda,
           mintAuthority: recipientKeypair.publicKey,
+          ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
         })
         .signers([recipientKeypair])
         .rpc();
@@ -1107,4 +3228,637 @@ describe("WUSD Token Test", () => {
       throw error;
     }
   });
+
+  it("Restrict operator permissions", async () => {
+    const updatePermissions = (permissions: number) =>
+      program.methods
+        .updateOperatorPermissions(recipientKeypair.publicKey, permissions)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          operatorAdminRole: rolePda(ROLES.operatorAdmin),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+        })
+        .rpc();
+
+    // 仅保留转账权限后，销毁应被拒绝
+    await updatePermissions(PERMISSIONS.transfer);
+    const record = await program.account.operatorRecord.fetch(
+      operatorPda(recipientKeypair.publicKey)
+    );
+    assert.equal(record.permissions, PERMISSIONS.transfer);
+
+    try {
+      await program.methods
+        .burn(new anchor.BN(1000000))
+        .accounts({
+          authorityState: authorityPda,
+          authority: recipientKeypair.publicKey,
+          mint: mintKeypair.publicKey,
+          tokenAccount: recipientTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          mintState: mintStatePda,
+          pauseState: pauseStatePda,
+          accessRegistry: accessRegistryPda,
+          mintAuthority: recipientKeypair.publicKey,
+          ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+        })
+        .signers([recipientKeypair])
+        .rpc();
+      assert.fail("burn without CAN_BURN should fail");
+    } catch (error) {
+      assert.include(error.toString(), "AccessDenied");
+    }
+
+    // 未定义的权限位被拒绝
+    try {
+      await updatePermissions(1 << 30);
+      assert.fail("undefined permission bits should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidPermissions");
+    }
+
+    await updatePermissions(PERMISSIONS.all);
+  });
+
+  it("Restrict recipients in allowlist mode", async () => {
+    await grantRole(ROLES.allowlister, provider.wallet.publicKey);
+
+    const setAllowlistMode = (enabled: boolean) =>
+      program.methods
+        .setAllowlistMode(enabled)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          accessRegistry: accessRegistryPda,
+        })
+        .rpc();
+    const manageAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      allowlisterRole: rolePda(ROLES.allowlister),
+      systemProgram: SystemProgram.programId,
+    };
+    const entries = [recipientKeypair.publicKey].map((account) => ({
+      pubkey: allowlistPda(account),
+      isSigner: false,
+      isWritable: true,
+    }));
+
+    await setAllowlistMode(true);
+    try {
+      await mintTokens(new anchor.BN(1000000));
+      assert.fail("mint to a wallet outside the allowlist should fail");
+    } catch (error) {
+      assert.include(error.toString(), "RecipientNotAllowlisted");
+    }
+
+    await program.methods
+      .addToAllowlist([recipientKeypair.publicKey])
+      .accounts(manageAccounts)
+      .remainingAccounts(entries)
+      .rpc();
+    await mintTokens(new anchor.BN(1000000));
+
+    // 赎回金库归authority_state所有，无需加入白名单即可接收赎回
+    const redemptionVault = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      authorityPda,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const requestId = new anchor.BN(Date.now());
+    const [redemptionRequest] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("redemption"),
+        mintKeypair.publicKey.toBuffer(),
+        recipientKeypair.publicKey.toBuffer(),
+        requestId.toArrayLike(Buffer, "le", 8),
+      ],
+      programId
+    );
+    await program.methods
+      .requestRedemption(requestId, new anchor.BN(1000000), Array.from(Buffer.alloc(32, 2)))
+      .accounts({
+        owner: recipientKeypair.publicKey,
+        tokenAccount: recipientTokenAccount,
+        tokenMint: mintKeypair.publicKey,
+        authorityState: authorityPda,
+        redemptionVault,
+        redemptionRequest,
+        pauseState: pauseStatePda,
+        accessRegistry: accessRegistryPda,
+        ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+        operatorRecord: operatorPda(recipientKeypair.publicKey),
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([recipientKeypair])
+      .rpc();
+    await program.methods
+      .rejectRedemption()
+      .accounts({
+        operator: provider.wallet.publicKey,
+        burnerRole: rolePda(ROLES.burner),
+        tokenMint: mintKeypair.publicKey,
+        authorityState: authorityPda,
+        redemptionVault,
+        redemptionRequest,
+        ownerBlacklist: blacklistPda(recipientKeypair.publicKey),
+        refundAccount: recipientTokenAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    assert.deepEqual(
+      (await program.account.redemptionRequest.fetch(redemptionRequest)).status,
+      { rejected: {} }
+    );
+
+    await program.methods
+      .removeFromAllowlist([recipientKeypair.publicKey])
+      .accounts(manageAccounts)
+      .remainingAccounts(entries)
+      .rpc();
+    assert.isNull(
+      await provider.connection.getAccountInfo(
+        allowlistPda(recipientKeypair.publicKey)
+      )
+    );
+
+    await setAllowlistMode(false);
+  });
+
+  it("Enforce KYC tier limits", async () => {
+    await grantRole(ROLES.compliance, provider.wallet.publicKey);
+
+    const setTierLimits = (tier: number, maxBalance: anchor.BN | null) =>
+      program.methods
+        .setKycTierLimits(tier, null, null, maxBalance)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          complianceRole: rolePda(ROLES.compliance),
+          tierLimits: kycTierPda(tier),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const kycAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      complianceRole: rolePda(ROLES.compliance),
+      kycRecord: kycPda(recipientKeypair.publicKey),
+    };
+
+    // 未验证等级的余额上限为当前余额，继续铸币被拒绝
+    const balance = await provider.connection.getTokenAccountBalance(
+      recipientTokenAccount
+    );
+    await setTierLimits(0, new anchor.BN(balance.value.amount));
+    try {
+      await mintTokens(new anchor.BN(1000000));
+      assert.fail("mint above the tier balance limit should fail");
+    } catch (error) {
+      assert.include(error.toString(), "BalanceLimitExceeded");
+    }
+
+    // 司法管辖区代码须为大写字母
+    try {
+      await program.methods
+        .setKycRecord(recipientKeypair.publicKey, 1, new anchor.BN(0), [0x75, 0x73])
+        .accounts({ ...kycAccounts, systemProgram: SystemProgram.programId })
+        .rpc();
+      assert.fail("lowercase jurisdiction should be rejected");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidJurisdiction");
+    }
+
+    // 升级到等级1（未设置限额）后可以继续铸币
+    await program.methods
+      .setKycRecord(recipientKeypair.publicKey, 1, new anchor.BN(0), [0x55, 0x53])
+      .accounts({ ...kycAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    const record = await program.account.kycRecord.fetch(
+      kycPda(recipientKeypair.publicKey)
+    );
+    assert.equal(record.tier, 1);
+    assert.equal(Buffer.from(record.jurisdiction).toString(), "US");
+    await mintTokens(new anchor.BN(1000000), 1);
+
+    await program.methods
+      .removeKycRecord(recipientKeypair.publicKey)
+      .accounts(kycAccounts)
+      .rpc();
+    await setTierLimits(0, null);
+  });
+
+  it("Enforce the KYC daily outflow window", async () => {
+    const setTierLimits = (maxDailyOutflow: anchor.BN | null) =>
+      program.methods
+        .setKycTierLimits(0, null, maxDailyOutflow, null)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          complianceRole: rolePda(ROLES.compliance),
+          tierLimits: kycTierPda(0),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const kycAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: mintKeypair.publicKey,
+      complianceRole: rolePda(ROLES.compliance),
+      kycRecord: kycPda(recipientKeypair.publicKey),
+    };
+
+    const receiver = Keypair.generate();
+    const receiverTokenAccount = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      receiver.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          receiverTokenAccount,
+          receiver.publicKey,
+          mintKeypair.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        )
+      )
+    );
+    const freezePda = (tokenAccount: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("freeze"), tokenAccount.toBuffer()],
+        program.programId
+      )[0];
+    await program.methods
+      .initializeFreezeState()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        freezeState: freezePda(receiverTokenAccount),
+        tokenAccount: receiverTokenAccount,
+        payer: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const transfer = (amount: number) =>
+      program.methods
+        .transfer(new anchor.BN(amount), null)
+        .accounts({
+          from: recipientKeypair.publicKey,
+          to: receiver.publicKey,
+          fromToken: recipientTokenAccount,
+          toToken: receiverTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenMint: mintKeypair.publicKey,
+          mintState: mintStatePda,
+          pauseState: pauseStatePda,
+          accessRegistry: accessRegistryPda,
+          fromFreezeState: freezePda(recipientTokenAccount),
+          toFreezeState: freezePda(receiverTokenAccount),
+          fromBlacklist: blacklistPda(recipientKeypair.publicKey),
+          toBlacklist: blacklistPda(receiver.publicKey),
+          recipientAllowlist: allowlistPda(receiver.publicKey),
+          fromKyc: kycPda(recipientKeypair.publicKey),
+          fromKycLimits: kycTierPda(),
+          fromKycOutflow: kycOutflowPda(recipientKeypair.publicKey),
+          toKyc: kycPda(receiver.publicKey),
+          toKycLimits: kycTierPda(),
+          operatorRecord: operatorPda(recipientKeypair.publicKey),
+          memoProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([recipientKeypair])
+        .rpc();
+
+    // 未验证钱包同样受每日流出上限约束，首笔转账时创建流出累计账户
+    await setTierLimits(new anchor.BN(3000000));
+    await transfer(2000000);
+    const outflow = await program.account.kycOutflow.fetch(
+      kycOutflowPda(recipientKeypair.publicKey)
+    );
+    assert.equal(outflow.dailyOutflow.amount.toNumber(), 2000000);
+    assert.equal(
+      outflow.dailyOutflow.day.toNumber(),
+      Math.floor(Date.now() / 1000 / 86400)
+    );
+
+    try {
+      await transfer(1500000);
+      assert.fail("transfer above the daily outflow limit should fail");
+    } catch (error) {
+      assert.include(error.toString(), "DailyOutflowLimitExceeded");
+    }
+
+    // 删除并重新创建KYC记录不会重置当日累计
+    await program.methods
+      .setKycRecord(recipientKeypair.publicKey, 0, new anchor.BN(0), [0x55, 0x53])
+      .accounts({ ...kycAccounts, systemProgram: SystemProgram.programId })
+      .rpc();
+    await program.methods
+      .removeKycRecord(recipientKeypair.publicKey)
+      .accounts(kycAccounts)
+      .rpc();
+    try {
+      await transfer(1500000);
+      assert.fail("re-creating the KYC record should not reset the outflow");
+    } catch (error) {
+      assert.include(error.toString(), "DailyOutflowLimitExceeded");
+    }
+
+    // 剩余额度内的转账仍可执行
+    await transfer(1000000);
+    assert.equal(
+      (
+        await program.account.kycOutflow.fetch(
+          kycOutflowPda(recipientKeypair.publicKey)
+        )
+      ).dailyOutflow.amount.toNumber(),
+      3000000
+    );
+
+    await setTierLimits(null);
+  });
+
+  it("Enforce KYC tier limits on direct Token-2022 transfers", async () => {
+    const {
+      mint,
+      hookProgram,
+      walletPda,
+      roleOf,
+      tierOf,
+      ata,
+      grant,
+      holder,
+      receiver,
+      transfer,
+    } = await initializeHookMint();
+    await grant(ROLES.compliance, provider.wallet.publicKey);
+    const setTierLimits = (
+      tier: number,
+      maxSingleTransfer: number | null,
+      maxDailyOutflow: number | null,
+      maxBalance: number | null
+    ) =>
+      program.methods
+        .setKycTierLimits(
+          tier,
+          maxSingleTransfer === null ? null : new anchor.BN(maxSingleTransfer),
+          maxDailyOutflow === null ? null : new anchor.BN(maxDailyOutflow),
+          maxBalance === null ? null : new anchor.BN(maxBalance)
+        )
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mint.publicKey,
+          complianceRole: roleOf(ROLES.compliance),
+          tierLimits: tierOf(tier),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const setKycRecord = (wallet: PublicKey, tier: number) =>
+      program.methods
+        .setKycRecord(wallet, tier, new anchor.BN(0), [0x55, 0x53])
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mint.publicKey,
+          complianceRole: roleOf(ROLES.compliance),
+          kycRecord: walletPda("kyc", wallet),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const [transferOutflow] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("kyc_outflow"),
+        mint.publicKey.toBuffer(),
+        holder.publicKey.toBuffer(),
+      ],
+      hookProgram.programId
+    );
+
+    // 额外账户列表为每个等级包含一个限额账户，等级超出范围被拒绝
+    await expectFailure(setKycRecord(receiver.publicKey, 3), "InvalidKycTier");
+
+    // 未验证等级：单笔上限2 WUSD，每日流出上限3 WUSD
+    await setTierLimits(0, 2000000, 3000000, null);
+    await expectFailure(
+      transfer(holder, receiver.publicKey, 2500000),
+      "TransferLimitExceeded"
+    );
+
+    // 钩子无法支付租金，须先创建流出累计账户
+    await expectFailure(
+      transfer(holder, receiver.publicKey, 1000000),
+      "KycOutflowNotInitialized"
+    );
+    await hookProgram.methods
+      .initializeTransferOutflow(holder.publicKey)
+      .accounts({
+        payer: provider.wallet.publicKey,
+        mint: mint.publicKey,
+        transferOutflow,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await transfer(holder, receiver.publicKey, 2000000);
+    await expectFailure(
+      transfer(holder, receiver.publicKey, 1500000),
+      "DailyOutflowLimitExceeded"
+    );
+    await transfer(holder, receiver.publicKey, 1000000);
+    const outflow = await hookProgram.account.transferOutflow.fetch(
+      transferOutflow
+    );
+    assert.equal(outflow.dailyOutflow.amount.toNumber(), 3000000);
+
+    // 余额上限按接收代币账户入账后的余额计算
+    await setTierLimits(0, null, null, 3000000);
+    await expectFailure(
+      transfer(holder, receiver.publicKey, 1),
+      "BalanceLimitExceeded"
+    );
+
+    // 升级到未设置限额的等级1后可以继续接收
+    await setKycRecord(receiver.publicKey, 1);
+    await transfer(holder, receiver.publicKey, 1000000);
+    const balance = await getAccount(
+      provider.connection,
+      ata(receiver.publicKey),
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(balance.amount.toString(), "4000000");
+  });
+
+  it("Initialize and update token metadata", async () => {
+    // 通过initialize创建带元数据的新mint
+    const { mint: metadataMint, authority: metadataAuthority } =
+      await initializeMint();
+
+    let metadata = await getTokenMetadata(
+      provider.connection,
+      metadataMint.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(metadata.name, "Wrapped USD");
+    assert.equal(metadata.symbol, "WUSD");
+    assert.equal(metadata.uri, "https://example.com/wusd.json");
+    assert.isTrue(metadata.updateAuthority.equals(metadataAuthority));
+
+    const updateAccounts = {
+      authority: provider.wallet.publicKey,
+      tokenMint: metadataMint.publicKey,
+      authorityState: metadataAuthority,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    };
+
+    // 管理员更新URI并添加附加字段
+    await program.methods
+      .updateMetadata({ uri: {} }, "https://example.com/wusd-v2.json")
+      .accounts(updateAccounts)
+      .rpc();
+    await program.methods
+      .updateMetadata({ key: ["issuer"] }, "WUSD Issuer")
+      .accounts(updateAccounts)
+      .rpc();
+
+    metadata = await getTokenMetadata(
+      provider.connection,
+      metadataMint.publicKey,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(metadata.name, "Wrapped USD");
+    assert.equal(metadata.uri, "https://example.com/wusd-v2.json");
+    assert.deepEqual(metadata.additionalMetadata, [["issuer", "WUSD Issuer"]]);
+
+    // 非管理员无法更新元数据
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .updateMetadata({ name: {} }, "Fake USD")
+        .accounts({ ...updateAccounts, authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("non-admin metadata update should fail");
+    } catch (error) {
+      assert.include(error.toString(), "Unauthorized");
+    }
+  });
+
+  it("Rescue other tokens held by the authority PDA", async () => {
+    await grantRole(ROLES.rescuer, provider.wallet.publicKey);
+
+    // 创建另一种代币，并误转入authority_state名下的账户
+    const strayMint = Keypair.generate();
+    const mintRent =
+      await provider.connection.getMinimumBalanceForRentExemption(82);
+    const strayVault = getAssociatedTokenAddressSync(
+      strayMint.publicKey,
+      authorityPda,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const walletAccount = getAssociatedTokenAddressSync(
+      strayMint.publicKey,
+      provider.wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: strayMint.publicKey,
+          space: 82,
+          lamports: mintRent,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeMint2Instruction(
+          strayMint.publicKey,
+          6,
+          provider.wallet.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          strayVault,
+          authorityPda,
+          strayMint.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createAssociatedTokenAccountInstruction(
+          provider.wallet.publicKey,
+          walletAccount,
+          provider.wallet.publicKey,
+          strayMint.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createMintToInstruction(
+          strayMint.publicKey,
+          strayVault,
+          provider.wallet.publicKey,
+          5000000,
+          [],
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [strayMint]
+    );
+
+    await program.methods
+      .rescueTokens(new anchor.BN(5000000))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        tokenMint: mintKeypair.publicKey,
+        authorityState: authorityPda,
+        rescuerRole: rolePda(ROLES.rescuer),
+        rescueMint: strayMint.publicKey,
+        source: strayVault,
+        destination: walletAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+    const rescued = await getAccount(
+      provider.connection,
+      walletAccount,
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(rescued.amount.toString(), "5000000");
+
+    // WUSD本身不可救援
+    const wusdVault = getAssociatedTokenAddressSync(
+      mintKeypair.publicKey,
+      authorityPda,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    try {
+      await program.methods
+        .rescueTokens(new anchor.BN(1))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          tokenMint: mintKeypair.publicKey,
+          authorityState: authorityPda,
+          rescuerRole: rolePda(ROLES.rescuer),
+          rescueMint: mintKeypair.publicKey,
+          source: wusdVault,
+          destination: recipientTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("rescuing WUSD should fail");
+    } catch (error) {
+      assert.include(error.toString(), "InvalidMint");
+    }
+  });
 });